@@ -2,7 +2,7 @@ use geo_types::{Coord, LineString, Point, Polygon};
 use omap::{
     objects::{AreaObject, LineObject, PointObject, TagTrait, TextObject},
     symbols::{AreaSymbol, LineSymbol, PointSymbol, TextSymbol},
-    Omap, Scale,
+    Crs, Omap, Scale,
 };
 use std::{path::PathBuf, str::FromStr};
 
@@ -17,7 +17,7 @@ fn main() {
     let mut omap = Omap::new(
         map_center,
         Scale::S15_000,
-        Some(crs_epsg_code),
+        Some(Crs::Epsg(crs_epsg_code)),
         Some(map_center_elevation_meters),
     )
     .expect("Could not make map with the given CRS-code");
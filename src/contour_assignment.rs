@@ -0,0 +1,82 @@
+use crate::{
+    map_part::MapPart,
+    objects::MapObject,
+    symbols::{LineSymbol, SprintLineSymbol, Symbol},
+};
+
+/// The lines changed by [Omap::assign_contour_symbols](crate::Omap::assign_contour_symbols)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContourAssignmentReport {
+    /// the number of lines moved to another symbol
+    pub num_reassigned: usize,
+    /// the elevations of the lines that are not on the equidistance or half of it, these lines are moved to the basemap contour symbol
+    pub mismatched_elevations: Vec<f64>,
+    /// the number of lines without an Elevation tag, these lines keep their symbol
+    pub num_untagged: usize,
+}
+
+/// Assign the contour symbols of the parts, see [Omap::assign_contour_symbols](crate::Omap::assign_contour_symbols)
+pub(crate) fn assign_contour_symbols(
+    parts: &mut [MapPart],
+    equidistance: f64,
+    index_interval: f64,
+) -> ContourAssignmentReport {
+    // the contour, index contour, form line and basemap contour of each symbol set
+    let families = [
+        [
+            LineSymbol::Contour,
+            LineSymbol::IndexContour,
+            LineSymbol::FormLine,
+            LineSymbol::BasemapContour,
+        ],
+        [
+            SprintLineSymbol::Contour,
+            SprintLineSymbol::IndexContour,
+            SprintLineSymbol::FormLine,
+            SprintLineSymbol::BasemapContour,
+        ]
+        .map(LineSymbol::Sprint),
+    ];
+    // elevations are compared with some slack for decimal tags
+    let is_multiple = |elevation: f64, interval: f64| {
+        let n = elevation / interval;
+        interval > 0. && (n - n.round()).abs() < 1e-6
+    };
+
+    let mut report = ContourAssignmentReport::default();
+    for part in parts.iter_mut() {
+        for family in families {
+            let [contour, index_contour, form_line, basemap_contour] = family;
+
+            let lines: Vec<MapObject> = family
+                .iter()
+                .filter_map(|s| part.objects.remove(&Symbol::Line(*s)))
+                .flatten()
+                .collect();
+            for mut line in lines {
+                let elevation = line.elevation();
+                if let MapObject::LineObject(o) = &mut line {
+                    let symbol = match elevation {
+                        None => {
+                            report.num_untagged += 1;
+                            o.symbol
+                        }
+                        Some(e) if is_multiple(e, index_interval) => index_contour,
+                        Some(e) if is_multiple(e, equidistance) => contour,
+                        Some(e) if is_multiple(e, equidistance / 2.) => form_line,
+                        Some(e) => {
+                            report.mismatched_elevations.push(e);
+                            basemap_contour
+                        }
+                    };
+                    if o.symbol != symbol {
+                        o.symbol = symbol;
+                        report.num_reassigned += 1;
+                    }
+                }
+                part.add_object(line);
+            }
+        }
+    }
+    report
+}
@@ -0,0 +1,501 @@
+use std::collections::HashMap;
+
+use geo_types::{Coord, LineString, Point};
+
+use crate::{
+    contour_loop::ContourLoop,
+    coordinate_mode::ToRelative,
+    geometry::{line_string_signed_area, measured_object},
+    map_part::MapPart,
+    objects::{LineObject, MapObject, TextObject},
+    symbols::{LineSymbol, SprintLineSymbol, SprintTextSymbol, Symbol, SymbolSet, TextSymbol},
+    OmapResult,
+};
+
+/// Label the index contours of the parts, see [Omap::add_contour_labels](crate::Omap::add_contour_labels)
+pub(crate) fn add_contour_labels(
+    parts: &mut [MapPart],
+    to_relative: &ToRelative,
+    symbol_set: &SymbolSet,
+    mm_per_meter: f64,
+    spacing: f64,
+) -> OmapResult<usize> {
+    let keys = [
+        (LineSymbol::IndexContour, TextSymbol::ContourValue),
+        (
+            LineSymbol::Sprint(SprintLineSymbol::IndexContour),
+            TextSymbol::Sprint(SprintTextSymbol::ContourValue),
+        ),
+    ];
+    let contour_symbols = [
+        LineSymbol::Contour,
+        LineSymbol::IndexContour,
+        LineSymbol::FormLine,
+        LineSymbol::Sprint(SprintLineSymbol::Contour),
+        LineSymbol::Sprint(SprintLineSymbol::IndexContour),
+        LineSymbol::Sprint(SprintLineSymbol::FormLine),
+    ]
+    .map(Symbol::Line);
+
+    // the label is 1.5 mm high on the map and enlarged with the rest of the symbol set
+    let meters_per_mm = match symbol_set {
+        SymbolSet::Isom2017_2Enlarged => 1.5,
+        _ => 1.,
+    } / mm_per_meter;
+    let size = LabelSize {
+        half_height: (0.75 + LABEL_MARGIN) * meters_per_mm,
+        char_width: LABEL_CHAR_WIDTH * meters_per_mm,
+        margin: LABEL_MARGIN * meters_per_mm,
+    };
+    let spacing = spacing * meters_per_mm;
+
+    let mut num_labels = 0;
+    for part in parts.iter_mut() {
+        let loops = ContourLoop::collect(part, &contour_symbols, to_relative)?;
+
+        // everything a label must keep clear of, index contours are identified by their key and position
+        let mut grid = LabelGrid::new(2. * size.half_height);
+        for (symbol, objects) in part.objects.iter() {
+            let key = keys.iter().position(|(l, _)| Symbol::Line(*l) == *symbol);
+            let is_contour = contour_symbols.contains(symbol);
+            for (i, obj) in objects.iter().enumerate() {
+                let owner = key.map(|k| (k, i));
+                let elevation = obj.elevation().filter(|_| is_contour);
+                let measured = measured_object(to_relative, obj)?;
+                for_each_sample(&measured, size.half_height, |c| {
+                    grid.insert(c, owner, elevation)
+                });
+            }
+        }
+
+        for (k, (line_symbol, text_symbol)) in keys.into_iter().enumerate() {
+            let Some(contours) = part.objects.remove(&Symbol::Line(line_symbol)) else {
+                continue;
+            };
+            let mut labels = Vec::new();
+            let mut lines = Vec::with_capacity(contours.len());
+            for (i, contour) in contours.into_iter().enumerate() {
+                let elevation = contour.elevation();
+                let (MapObject::LineObject(o), Some(elevation)) = (&contour, elevation) else {
+                    lines.push(contour);
+                    continue;
+                };
+                let measured_contour = measured_object(to_relative, &contour)?;
+                let MapObject::LineObject(measured) = measured_contour.as_ref() else {
+                    unreachable!("measuring a line object gives a line object");
+                };
+                let text = format!("{elevation}");
+                let half_length = text.chars().count() as f64 * size.char_width / 2. + size.margin;
+                let Some(path) = LabelPath::new(&measured.line.0, &o.line.0) else {
+                    lines.push(contour);
+                    continue;
+                };
+                let placements = path.placements(spacing, half_length, &size, &grid, (k, i));
+                if placements.is_empty() {
+                    lines.push(contour);
+                    continue;
+                }
+
+                for &s in placements.iter() {
+                    let (center, direction) = path.center_direction(s, half_length);
+                    let uphill_is_left = grid
+                        .uphill_is_left(
+                            center,
+                            direction,
+                            half_length,
+                            spacing / 2.,
+                            elevation,
+                            (k, i),
+                        )
+                        .or_else(|| {
+                            let inside_is_left = line_string_signed_area(&measured.line) > 0.;
+                            let inside_is_higher =
+                                match ContourLoop::relief(&loops, &measured_contour) {
+                                    Some(relief) => relief > 0.,
+                                    None => inside_is_left,
+                                };
+                            path.closed.then_some(inside_is_higher == inside_is_left)
+                        })
+                        // keep labels on open contours without neighbours readable
+                        .unwrap_or(direction.x >= 0.);
+                    let rotation = if uphill_is_left {
+                        direction.y.atan2(direction.x)
+                    } else {
+                        (-direction.y).atan2(-direction.x)
+                    };
+
+                    let mut label = TextObject::from_point(
+                        Point(path.original_at(s)),
+                        text_symbol,
+                        text.clone(),
+                    );
+                    label.rotation = Some(rotation);
+                    labels.push(label);
+                    grid.insert_label(center, direction, half_length, size.half_height);
+                }
+
+                for piece in path.cut(&placements, half_length) {
+                    let mut line = LineObject::from_line_string(piece, o.symbol);
+                    line.tags = o.tags.clone();
+                    lines.push(MapObject::LineObject(line));
+                }
+            }
+            num_labels += labels.len();
+            for line in lines {
+                part.add_object(line);
+            }
+            for label in labels {
+                part.add_object(label);
+            }
+        }
+    }
+    Ok(num_labels)
+}
+
+// the width of a digit and the clearance around contour labels in millimetres on the map
+const LABEL_CHAR_WIDTH: f64 = 1.15;
+const LABEL_MARGIN: f64 = 0.3;
+
+// the dimensions of a contour label in projected meters
+struct LabelSize {
+    // half the height of the label including the margin
+    half_height: f64,
+    char_width: f64,
+    margin: f64,
+}
+
+// a sampled coordinate with the index contour it belongs to and the elevation of contours
+type LabelGridEntry = (Coord, Option<(usize, usize)>, Option<f64>);
+
+// the sampled coordinates of the objects of a map part in square cells
+struct LabelGrid {
+    cell: f64,
+    cells: HashMap<(i64, i64), Vec<LabelGridEntry>>,
+}
+
+impl LabelGrid {
+    fn new(cell: f64) -> Self {
+        LabelGrid {
+            cell,
+            cells: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, c: Coord, owner: Option<(usize, usize)>, elevation: Option<f64>) {
+        let key = (
+            (c.x / self.cell).floor() as i64,
+            (c.y / self.cell).floor() as i64,
+        );
+        self.cells
+            .entry(key)
+            .or_default()
+            .push((c, owner, elevation));
+    }
+
+    // cover the area of a placed label, so no other label is placed over it
+    fn insert_label(
+        &mut self,
+        center: Coord,
+        direction: Coord,
+        half_length: f64,
+        half_height: f64,
+    ) {
+        let normal = Coord {
+            x: -direction.y,
+            y: direction.x,
+        };
+        let steps = (half_length / half_height).ceil() as i32;
+        for i in -steps..=steps {
+            let along = direction * (half_length * i as f64 / steps as f64);
+            for j in -1..=1 {
+                self.insert(
+                    center + along + normal * (half_height * j as f64),
+                    None,
+                    None,
+                );
+            }
+        }
+    }
+
+    // the sampled coordinates within `radius` of `c` and not on the `owner` contour,
+    // in the frame of the `direction` as (along, across, elevation)
+    fn near(
+        &self,
+        c: Coord,
+        direction: Coord,
+        radius: f64,
+        owner: (usize, usize),
+    ) -> impl Iterator<Item = (f64, f64, Option<f64>)> + '_ {
+        let range = |v: f64| {
+            ((v - radius) / self.cell).floor() as i64..=((v + radius) / self.cell).floor() as i64
+        };
+        let ys = range(c.y);
+        range(c.x)
+            .flat_map(move |x| ys.clone().filter_map(move |y| self.cells.get(&(x, y))))
+            .flatten()
+            .filter(move |(_, o, _)| *o != Some(owner))
+            .map(move |&(p, _, elevation)| {
+                let d = p - c;
+                (
+                    d.x * direction.x + d.y * direction.y,
+                    d.y * direction.x - d.x * direction.y,
+                    elevation,
+                )
+            })
+    }
+
+    // is anything but the `owner` contour under a label at `center` along the `direction`
+    fn is_cluttered(
+        &self,
+        center: Coord,
+        direction: Coord,
+        half_length: f64,
+        half_height: f64,
+        owner: (usize, usize),
+    ) -> bool {
+        self.near(center, direction, half_length + half_height, owner)
+            .any(|(along, across, _)| along.abs() < half_length && across.abs() < half_height)
+    }
+
+    // is the higher side of the `owner` contour to the left of the `direction`,
+    // from the nearest contour with another elevation beside a label at `center`
+    fn uphill_is_left(
+        &self,
+        center: Coord,
+        direction: Coord,
+        half_length: f64,
+        radius: f64,
+        elevation: f64,
+        owner: (usize, usize),
+    ) -> Option<bool> {
+        self.near(center, direction, radius, owner)
+            .filter_map(|(along, across, e)| {
+                let e = e?;
+                (along.abs() < half_length && across != 0. && e != elevation).then_some((across, e))
+            })
+            .min_by(|a, b| a.0.abs().total_cmp(&b.0.abs()))
+            .map(|(across, e)| (e > elevation) == (across > 0.))
+    }
+}
+
+// call `f` with the vertices of an object and points on its segments at most `step` apart
+fn for_each_sample(obj: &MapObject, step: f64, mut f: impl FnMut(Coord)) {
+    let mut sample = |coords: &[Coord]| {
+        for w in coords.windows(2) {
+            let d = w[1] - w[0];
+            let n = (d.x.hypot(d.y) / step).ceil().max(1.);
+            for i in 0..n as usize {
+                f(w[0] + d * (i as f64 / n));
+            }
+        }
+        if let Some(&last) = coords.last() {
+            f(last);
+        }
+    };
+    match obj {
+        MapObject::LineObject(o) => sample(&o.line.0),
+        MapObject::AreaObject(o) => {
+            sample(&o.polygon.exterior().0);
+            for interior in o.polygon.interiors() {
+                sample(&interior.0);
+            }
+        }
+        MapObject::PointObject(o) => sample(std::slice::from_ref(&o.point.0)),
+        MapObject::TextObject(o) => sample(std::slice::from_ref(&o.point.0)),
+    }
+}
+
+// a contour measured by the distance along it in projected meters, closed contours are unrolled to twice their length
+struct LabelPath {
+    measured: Vec<Coord>,
+    original: Vec<Coord>,
+    distances: Vec<f64>,
+    length: f64,
+    closed: bool,
+}
+
+impl LabelPath {
+    fn new(measured: &[Coord], original: &[Coord]) -> Option<Self> {
+        if measured.len() < 2 || measured.len() != original.len() {
+            return None;
+        }
+        let closed = measured.len() >= 4 && measured.first() == measured.last();
+        let (measured, original) = if closed {
+            (
+                [measured, &measured[1..]].concat(),
+                [original, &original[1..]].concat(),
+            )
+        } else {
+            (measured.to_vec(), original.to_vec())
+        };
+
+        let mut distances = Vec::with_capacity(measured.len());
+        let mut distance = 0.;
+        distances.push(distance);
+        for w in measured.windows(2) {
+            distance += (w[1] - w[0]).x.hypot((w[1] - w[0]).y);
+            distances.push(distance);
+        }
+        let length = if closed { distance / 2. } else { distance };
+        (length > 0.).then_some(LabelPath {
+            measured,
+            original,
+            distances,
+            length,
+            closed,
+        })
+    }
+
+    // the coordinate at the distance `s` along the line
+    fn at(&self, coords: &[Coord], s: f64) -> Coord {
+        let i = self
+            .distances
+            .partition_point(|d| *d <= s)
+            .clamp(1, coords.len() - 1)
+            - 1;
+        let segment = self.distances[i + 1] - self.distances[i];
+        let t = if segment > 0. {
+            ((s - self.distances[i]) / segment).clamp(0., 1.)
+        } else {
+            0.
+        };
+        coords[i] + (coords[i + 1] - coords[i]) * t
+    }
+
+    fn original_at(&self, s: f64) -> Coord {
+        self.at(&self.original, s)
+    }
+
+    // the measured centre and unit direction of the chord under a label at `s`
+    fn center_direction(&self, s: f64, half_length: f64) -> (Coord, Coord) {
+        let chord =
+            self.at(&self.measured, s + half_length) - self.at(&self.measured, s - half_length);
+        let length = chord.x.hypot(chord.y);
+        let direction = if length > 0. {
+            chord / length
+        } else {
+            Coord { x: 1., y: 0. }
+        };
+        (self.at(&self.measured, s), direction)
+    }
+
+    // the largest distance of the line under a label at `s` from its chord
+    fn deviation(&self, s: f64, half_length: f64) -> f64 {
+        let (a, b) = (
+            self.at(&self.measured, s - half_length),
+            self.at(&self.measured, s + half_length),
+        );
+        let chord = b - a;
+        let length = chord.x.hypot(chord.y);
+        if length < half_length {
+            return f64::INFINITY;
+        }
+        self.distances
+            .iter()
+            .zip(self.measured.iter())
+            .filter(|(d, _)| (s - half_length..s + half_length).contains(*d))
+            .map(|(_, &c)| (chord.x * (c - a).y - chord.y * (c - a).x).abs() / length)
+            .fold(0., f64::max)
+    }
+
+    // the distances along the line of the labels, sorted.
+    // The line is divided into arcs of about `spacing` length and a label is placed at the straightest uncluttered spot of each arc
+    fn placements(
+        &self,
+        spacing: f64,
+        half_length: f64,
+        size: &LabelSize,
+        grid: &LabelGrid,
+        owner: (usize, usize),
+    ) -> Vec<f64> {
+        // closed lines are searched once around from the first spot a label fits
+        let (start, end) = if self.closed {
+            if self.length < 2. * half_length {
+                return Vec::new();
+            }
+            (half_length, self.length + half_length)
+        } else {
+            (half_length, self.length - half_length)
+        };
+        if end < start {
+            return Vec::new();
+        }
+        let num_arcs = ((end - start) / spacing).round().max(1.);
+        let max_deviation = size.half_height / 2.;
+        let step = size.half_height;
+
+        let mut placements: Vec<f64> = Vec::new();
+        for arc in 0..num_arcs as usize {
+            let arc_start = start + (end - start) * arc as f64 / num_arcs;
+            let arc_end = start + (end - start) * (arc + 1) as f64 / num_arcs;
+            let arc_middle = (arc_start + arc_end) / 2.;
+            let mut best: Option<(f64, f64)> = None;
+            let mut s = arc_start;
+            while s <= arc_end {
+                let deviation = self.deviation(s, half_length);
+                // equally straight spots are preferred in the middle of the arc
+                let score = deviation
+                    + max_deviation * (s - arc_middle).abs() / (arc_end - arc_start).max(step);
+                if deviation < max_deviation && best.is_none_or(|(_, b)| score < b) {
+                    let (center, direction) = self.center_direction(s, half_length);
+                    if !grid.is_cluttered(center, direction, half_length, size.half_height, owner) {
+                        best = Some((s, score));
+                    }
+                }
+                s += step;
+            }
+            // labels in neighbouring arcs are kept apart
+            let min_distance = (spacing / 2.).max(3. * half_length);
+            if let Some((s, _)) = best {
+                let is_apart = placements.last().is_none_or(|p| s - p >= min_distance)
+                    && (!self.closed
+                        || placements
+                            .first()
+                            .is_none_or(|p| p + self.length - s >= min_distance));
+                if is_apart {
+                    placements.push(s);
+                }
+            }
+        }
+        placements
+    }
+
+    // the pieces of the original line between gaps under the labels at the sorted `placements`
+    fn cut(&self, placements: &[f64], half_length: f64) -> Vec<LineString> {
+        let gaps = placements
+            .iter()
+            .map(|s| (s - half_length, s + half_length))
+            .collect::<Vec<_>>();
+        let mut ranges = Vec::with_capacity(gaps.len() + 1);
+        if self.closed {
+            for (i, &(_, from)) in gaps.iter().enumerate() {
+                let to = gaps.get(i + 1).map_or(gaps[0].0 + self.length, |g| g.0);
+                ranges.push((from, to));
+            }
+        } else {
+            let mut from = 0.;
+            for &(to, next) in gaps.iter() {
+                ranges.push((from, to));
+                from = next;
+            }
+            ranges.push((from, self.length));
+        }
+
+        ranges
+            .into_iter()
+            .filter(|(from, to)| to > from)
+            .map(|(from, to)| {
+                let mut coords = vec![self.original_at(from)];
+                coords.extend(
+                    self.distances
+                        .iter()
+                        .zip(self.original.iter())
+                        .filter(|(d, _)| **d > from && **d < to)
+                        .map(|(_, &c)| c),
+                );
+                coords.push(self.original_at(to));
+                LineString::new(coords)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,100 @@
+use geo_types::{Coord, LineString};
+
+use crate::{
+    coordinate_mode::ToRelative,
+    geometry::{line_string_signed_area, measured_object},
+    map_part::MapPart,
+    objects::MapObject,
+    symbols::Symbol,
+    OmapResult,
+};
+
+// a closed contour in projected coordinates relative the ref point with its bounding box, area and elevation
+pub(crate) struct ContourLoop {
+    pub(crate) line: LineString,
+    pub(crate) min: Coord,
+    pub(crate) max: Coord,
+    pub(crate) area: f64,
+    pub(crate) elevation: f64,
+}
+
+impl ContourLoop {
+    // all closed contours with an elevation and one of the symbols in the part
+    pub(crate) fn collect(
+        part: &MapPart,
+        symbols: &[Symbol],
+        to_relative: &ToRelative,
+    ) -> OmapResult<Vec<Self>> {
+        let mut loops = Vec::new();
+        for obj in symbols
+            .iter()
+            .filter_map(|symbol| part.objects.get(symbol))
+            .flatten()
+        {
+            let (MapObject::LineObject(l), Some(elevation)) = (obj, obj.elevation()) else {
+                continue;
+            };
+            if !l.line.is_closed() {
+                continue;
+            }
+            if let MapObject::LineObject(measured) = measured_object(to_relative, obj)?.as_ref() {
+                loops.extend(ContourLoop::new(&measured.line, elevation));
+            }
+        }
+        Ok(loops)
+    }
+
+    // the height of a closed contour (measured like the loops) above the smallest loop enclosing it, negative for depressions.
+    // None if it has no elevation, no enclosing loop or the same elevation as it
+    pub(crate) fn relief(loops: &[Self], contour: &MapObject) -> Option<f64> {
+        let MapObject::LineObject(o) = contour else {
+            return None;
+        };
+        let elevation = contour.elevation()?;
+        let area = line_string_signed_area(&o.line).abs();
+        let enclosing = loops
+            .iter()
+            .filter(|l| l.area > area && l.contains(&o.line))
+            .min_by(|a, b| a.area.total_cmp(&b.area))?;
+        let relief = elevation - enclosing.elevation;
+        (relief != 0.).then_some(relief)
+    }
+
+    pub(crate) fn new(line: &LineString, elevation: f64) -> Option<Self> {
+        let mut min = *line.0.first()?;
+        let mut max = min;
+        for c in line.0.iter() {
+            min.x = min.x.min(c.x);
+            min.y = min.y.min(c.y);
+            max.x = max.x.max(c.x);
+            max.y = max.y.max(c.y);
+        }
+        Some(ContourLoop {
+            line: line.clone(),
+            min,
+            max,
+            area: line_string_signed_area(line).abs(),
+            elevation,
+        })
+    }
+
+    // contours do not cross, so another loop is inside if its first point is
+    pub(crate) fn contains(&self, other: &LineString) -> bool {
+        let Some(&p) = other.0.first() else {
+            return false;
+        };
+        if p.x < self.min.x || p.x > self.max.x || p.y < self.min.y || p.y > self.max.y {
+            return false;
+        }
+
+        // ray casting to the right of the point
+        let mut inside = false;
+        for segment in self.line.lines() {
+            let (a, b) = (segment.start, segment.end);
+            if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
@@ -0,0 +1,96 @@
+use geo_types::Coord;
+
+use crate::{objects::MapObject, Crs, OmapError, OmapResult};
+
+#[cfg(feature = "geo_ref")]
+use proj4rs::{transform::transform, Proj};
+
+/// The coordinates the objects of a map are given in
+///
+/// The objects are converted to coordinates relative the map's ref point when the map is written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoordinateMode {
+    /// Projected coordinates relative the map's ref point
+    #[default]
+    Relative,
+    /// Projected coordinates in the map's CRS
+    Absolute,
+    /// Geographic coordinates in degrees on WGS84 with x as longitude and y as latitude, they are projected to the map's CRS.
+    /// This mode is only available with the `geo_ref`-feature and requires the map to have a CRS
+    Geographic,
+}
+
+/// Conversion of object coordinates in a [CoordinateMode] to coordinates relative the ref point
+pub(crate) struct ToRelative {
+    ref_point: Coord,
+    offset: Coord,
+    #[cfg(feature = "geo_ref")]
+    projection: Option<(Proj, Proj)>,
+}
+
+impl ToRelative {
+    pub(crate) fn new(
+        mode: CoordinateMode,
+        ref_point: Coord,
+        #[allow(unused_variables)] crs: Option<&Crs>,
+    ) -> OmapResult<Self> {
+        match mode {
+            CoordinateMode::Relative => Ok(ToRelative {
+                ref_point,
+                offset: Coord::zero(),
+                #[cfg(feature = "geo_ref")]
+                projection: None,
+            }),
+            CoordinateMode::Absolute => Ok(ToRelative {
+                ref_point,
+                offset: -ref_point,
+                #[cfg(feature = "geo_ref")]
+                projection: None,
+            }),
+            CoordinateMode::Geographic => {
+                #[cfg(feature = "geo_ref")]
+                {
+                    let Some(crs) = crs else {
+                        return Err(OmapError::MissingCrs);
+                    };
+                    Ok(ToRelative {
+                        ref_point,
+                        offset: -ref_point,
+                        projection: Some((Proj::from_epsg_code(4326)?, crs.to_proj()?)),
+                    })
+                }
+                #[cfg(not(feature = "geo_ref"))]
+                Err(OmapError::DisabledGeoReferencingFeature)
+            }
+        }
+    }
+
+    /// The projected ref point the objects are made relative to
+    pub(crate) fn ref_point(&self) -> Coord {
+        self.ref_point
+    }
+
+    /// Check if the objects are already relative the ref point
+    pub(crate) fn is_identity(&self) -> bool {
+        #[cfg(feature = "geo_ref")]
+        if self.projection.is_some() {
+            return false;
+        }
+        self.offset == Coord::zero()
+    }
+
+    pub(crate) fn apply(&self, obj: &mut MapObject) -> OmapResult<()> {
+        obj.try_for_each_coord(|c| self.apply_coord(c))
+    }
+
+    pub(crate) fn apply_coord(&self, c: &mut Coord) -> OmapResult<()> {
+        #[cfg(feature = "geo_ref")]
+        if let Some((geo_proj, local_proj)) = &self.projection {
+            c.x = c.x.to_radians();
+            c.y = c.y.to_radians();
+            transform(geo_proj, local_proj, c)?;
+        }
+        *c = *c + self.offset;
+        Ok(())
+    }
+}
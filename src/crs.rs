@@ -0,0 +1,73 @@
+use std::fmt;
+
+#[cfg(feature = "geo_ref")]
+use crate::{OmapError, OmapResult};
+#[cfg(feature = "geo_ref")]
+use proj4rs::Proj;
+
+/// A projected Coordinate Reference System
+///
+/// EPSG codes and PROJ strings are used for the georeferencing computations with the `geo_ref`-feature.
+/// WKT definitions and EPSG codes above 65535 are written to the map as is and can not be used for computations,
+/// so a map with such a CRS must be built with an explicit declination or grivation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Crs {
+    /// A CRS by its EPSG code, e.g. 25832 for ETRS89 / UTM zone 32N
+    Epsg(u32),
+    /// A CRS by its PROJ definition, e.g. "+proj=tmerc +lat_0=58 +lon_0=10 +k=1 +x_0=100000 +y_0=1000000 +ellps=GRS80 +units=m +no_defs"
+    Proj(String),
+    /// A CRS by its WKT definition
+    Wkt(String),
+}
+
+impl Crs {
+    /// The definition of the CRS written to the `<spec>` element of the map
+    pub(crate) fn spec(&self) -> String {
+        match self {
+            Crs::Epsg(code) if u16::try_from(*code).is_ok() => format!("+init=epsg:{code}"),
+            // the init files of PROJ only hold the classic codes, newer ones are resolved by authority
+            Crs::Epsg(code) => format!("EPSG:{code}"),
+            Crs::Proj(proj) => proj.clone(),
+            Crs::Wkt(wkt) => wkt.clone(),
+        }
+    }
+
+    /// Get the projection for georeferencing computations
+    #[cfg(feature = "geo_ref")]
+    pub(crate) fn to_proj(&self) -> OmapResult<Proj> {
+        match self {
+            Crs::Epsg(code) => match u16::try_from(*code) {
+                Ok(code) => Ok(Proj::from_epsg_code(code)?),
+                Err(_) => Err(OmapError::UnsupportedCrs(self.clone())),
+            },
+            Crs::Proj(proj) => Ok(Proj::from_proj_string(proj)?),
+            Crs::Wkt(_) => Err(OmapError::UnsupportedCrs(self.clone())),
+        }
+    }
+
+    /// Whether the CRS can be used for georeferencing computations at all
+    #[cfg(feature = "geo_ref")]
+    pub(crate) fn supports_computations(&self) -> bool {
+        match self {
+            Crs::Epsg(code) => u16::try_from(*code).is_ok(),
+            Crs::Proj(_) => true,
+            Crs::Wkt(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for Crs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Crs::Epsg(code) => write!(f, "EPSG:{code}"),
+            Crs::Proj(proj) => write!(f, "{proj}"),
+            Crs::Wkt(wkt) => write!(f, "{wkt}"),
+        }
+    }
+}
+
+impl From<u32> for Crs {
+    fn from(value: u32) -> Self {
+        Crs::Epsg(value)
+    }
+}
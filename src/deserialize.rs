@@ -0,0 +1,453 @@
+use geo_types::{Coord, LineString, Point, Polygon};
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use std::{collections::HashMap, io::BufRead};
+
+use crate::{
+    map_part::MapPart,
+    objects::{AreaObject, LineObject, MapObject, PointObject, TextObject},
+    symbols::{Symbol, SymbolSet},
+    Crs, OmapError, OmapResult, Scale,
+};
+
+// path coordinate flags as defined by OpenOrienteering Mapper
+const CURVE_START: u8 = 1;
+const CLOSE_POINT: u8 = 2;
+const HOLE_POINT: u8 = 16;
+
+// max deviation in map units (0.001mm on paper) when flattening bezier curves
+const FLATTENING_TOLERANCE: f64 = 10.;
+
+/// The georeferencing of a map as read from the `<georeferencing>` element
+#[derive(Debug, Clone)]
+pub(crate) struct Georeferencing {
+    pub(crate) scale: Scale,
+    pub(crate) combined_scale_factor: f64,
+    pub(crate) elevation_scale_factor: f64,
+    pub(crate) declination: f64,
+    pub(crate) grivation: f64,
+    pub(crate) crs: Option<Crs>,
+    pub(crate) ref_point: Coord,
+    pub(crate) geo_ref_point: Option<Coord>,
+    // the map coordinate (in map units) of the ref point
+    pub(crate) map_ref_point: Coord,
+}
+
+impl Georeferencing {
+    pub(crate) fn to_projected(&self, c: Coord) -> Coord {
+        let conversion = self.scale.map_units_per_meter() / self.combined_scale_factor;
+
+        let x = (c.x - self.map_ref_point.x) / conversion;
+        let y = -(c.y - self.map_ref_point.y) / conversion;
+
+        let sin = self.grivation.sin();
+        let cos = self.grivation.cos();
+
+        Coord {
+            x: x * cos + y * sin,
+            y: -x * sin + y * cos,
+        }
+    }
+}
+
+/// Get the unescaped value of an attribute
+pub(crate) fn attribute(e: &BytesStart<'_>, name: &str) -> OmapResult<Option<String>> {
+    match e
+        .try_get_attribute(name)
+        .map_err(quick_xml::Error::InvalidAttr)?
+    {
+        Some(a) => Ok(Some(a.unescape_value()?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+pub(crate) fn parse_attribute<T: std::str::FromStr>(
+    e: &BytesStart<'_>,
+    name: &str,
+) -> OmapResult<Option<T>> {
+    match attribute(e, name)? {
+        Some(v) => v.trim().parse::<T>().map(Some).map_err(|_| {
+            OmapError::InvalidFile(format!("could not parse attribute {name}=\"{v}\""))
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Read the `<georeferencing>` element, the reader must be positioned right after its start tag
+pub(crate) fn read_georeferencing<R: BufRead>(
+    reader: &mut Reader<R>,
+    start: &BytesStart<'_>,
+) -> OmapResult<Georeferencing> {
+    let scale = match parse_attribute::<u32>(start, "scale")? {
+        Some(s) => {
+            Scale::new(s).ok_or_else(|| OmapError::InvalidFile("invalid scale 1:0".to_string()))?
+        }
+        None => return Err(OmapError::InvalidFile("map scale is missing".to_string())),
+    };
+
+    let mut georef = Georeferencing {
+        scale,
+        combined_scale_factor: parse_attribute(start, "grid_scale_factor")?.unwrap_or(1.),
+        elevation_scale_factor: parse_attribute(start, "auxiliary_scale_factor")?.unwrap_or(1.),
+        declination: parse_attribute::<f64>(start, "declination")?
+            .unwrap_or(0.)
+            .to_radians(),
+        grivation: parse_attribute::<f64>(start, "grivation")?
+            .unwrap_or(0.)
+            .to_radians(),
+        crs: None,
+        ref_point: Coord::zero(),
+        geo_ref_point: None,
+        map_ref_point: Coord::zero(),
+    };
+
+    let mut in_projected_crs = false;
+    let mut crs_id = None;
+    let mut in_parameter = false;
+    let mut in_spec = false;
+    let mut spec = String::new();
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                b"projected_crs" => {
+                    in_projected_crs = true;
+                    crs_id = attribute(&e, "id")?;
+                }
+                b"parameter" => in_parameter = in_projected_crs,
+                b"spec" => in_spec = in_projected_crs,
+                b"ref_point" => {
+                    let c = Coord {
+                        x: parse_attribute(&e, "x")?.unwrap_or(0.),
+                        y: parse_attribute(&e, "y")?.unwrap_or(0.),
+                    };
+                    if in_projected_crs {
+                        georef.ref_point = c;
+                    } else {
+                        georef.map_ref_point = c;
+                    }
+                }
+                b"ref_point_deg" => {
+                    let lat: f64 = parse_attribute(&e, "lat")?.unwrap_or(0.);
+                    let lon: f64 = parse_attribute(&e, "lon")?.unwrap_or(0.);
+                    georef.geo_ref_point = Some(Coord {
+                        x: lon.to_radians(),
+                        y: lat.to_radians(),
+                    });
+                }
+                _ => (),
+            },
+            Event::Text(t) if in_parameter && crs_id.as_deref() == Some("EPSG") => {
+                georef.crs = t.unescape()?.trim().parse().ok().map(Crs::Epsg);
+            }
+            Event::Text(t) if in_spec => spec.push_str(&t.unescape()?),
+            Event::End(e) => match e.name().as_ref() {
+                b"projected_crs" => in_projected_crs = false,
+                b"parameter" => in_parameter = false,
+                b"spec" => in_spec = false,
+                b"georeferencing" => break,
+                _ => (),
+            },
+            Event::Eof => {
+                return Err(OmapError::InvalidFile(
+                    "unexpected end of file in georeferencing".to_string(),
+                ))
+            }
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    // CRSs other than EPSG codes are only known by their spec
+    let spec = spec.trim();
+    if georef.crs.is_none() && !spec.is_empty() && crs_id.is_some_and(|id| id != "Local") {
+        georef.crs = Some(if spec.contains('[') {
+            Crs::Wkt(spec.to_string())
+        } else {
+            Crs::Proj(spec.to_string())
+        });
+    }
+    if georef.crs.is_none() {
+        georef.geo_ref_point = None;
+    }
+    Ok(georef)
+}
+
+/// Read all parts with their objects, the reader must be positioned right after the `<parts>` start tag
+/// Objects with symbols not in `symbol_set` are skipped
+pub(crate) fn read_parts<R: BufRead>(
+    reader: &mut Reader<R>,
+    georef: &Georeferencing,
+    symbol_set: &SymbolSet,
+) -> OmapResult<Vec<MapPart>> {
+    let mut parts: Vec<MapPart> = Vec::new();
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"part" => {
+                parts.push(MapPart::new(attribute(&e, "name")?.unwrap_or_default()));
+            }
+            Event::Start(e) if e.name().as_ref() == b"object" => {
+                let Some(part) = parts.last_mut() else {
+                    return Err(OmapError::InvalidFile(
+                        "an object is not in a map part".to_string(),
+                    ));
+                };
+                if let Some(obj) = read_object(reader, &e, georef, symbol_set)? {
+                    for o in obj {
+                        part.add_object(o);
+                    }
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"parts" => break,
+            Event::Eof => {
+                return Err(OmapError::InvalidFile(
+                    "unexpected end of file in parts".to_string(),
+                ))
+            }
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(parts)
+}
+
+fn read_object<R: BufRead>(
+    reader: &mut Reader<R>,
+    start: &BytesStart<'_>,
+    georef: &Georeferencing,
+    symbol_set: &SymbolSet,
+) -> OmapResult<Option<Vec<MapObject>>> {
+    let symbol = parse_attribute::<i32>(start, "symbol")?
+        .and_then(|id| u16::try_from(id).ok())
+        .and_then(|id| symbol_set.symbol(id));
+    let raw_rotation = parse_attribute::<f64>(start, "rotation")?;
+    let rotation = raw_rotation.unwrap_or(0.) - georef.grivation;
+
+    let mut tags = HashMap::new();
+    let mut coords: Vec<(Coord, u8)> = Vec::new();
+    let mut text = String::new();
+    let mut pattern_rotation = 0.;
+
+    let mut tag_key = None;
+    let mut in_coords = false;
+    let mut in_text = false;
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                b"t" => tag_key = Some(attribute(&e, "k")?.unwrap_or_default()),
+                b"coords" => in_coords = true,
+                b"coord" if in_coords => {
+                    coords.push((
+                        Coord {
+                            x: parse_attribute(&e, "x")?.unwrap_or(0.),
+                            y: parse_attribute(&e, "y")?.unwrap_or(0.),
+                        },
+                        parse_attribute(&e, "flags")?.unwrap_or(0),
+                    ));
+                }
+                b"text" => in_text = true,
+                b"pattern" => {
+                    pattern_rotation =
+                        parse_attribute::<f64>(&e, "rotation")?.unwrap_or(0.) - georef.grivation;
+                }
+                _ => (),
+            },
+            Event::Text(t) => {
+                let t = t.unescape()?;
+                if let Some(key) = tag_key.take() {
+                    let _ = tags.insert(key, t.into_owned());
+                } else if in_coords {
+                    coords.extend(parse_coords(&t)?);
+                } else if in_text {
+                    text.push_str(&t);
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                b"t" => {
+                    // a tag with an empty value gives no text event
+                    if let Some(key) = tag_key.take() {
+                        let _ = tags.insert(key, String::new());
+                    }
+                }
+                b"coords" => in_coords = false,
+                b"text" => in_text = false,
+                b"object" => break,
+                _ => (),
+            },
+            Event::Eof => {
+                return Err(OmapError::InvalidFile(
+                    "unexpected end of file in object".to_string(),
+                ))
+            }
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    let Some(symbol) = symbol else {
+        return Ok(None);
+    };
+    if coords.is_empty() {
+        return Err(OmapError::InvalidFile(format!(
+            "object with symbol {symbol} has no coordinates"
+        )));
+    }
+
+    let objects = match symbol {
+        Symbol::Area(symbol) => {
+            let mut rings = to_line_strings(&coords, georef).into_iter();
+            let mut exterior = rings.next().unwrap();
+            exterior.close();
+
+            let interiors = rings
+                .map(|mut r| {
+                    r.close();
+                    r
+                })
+                .collect();
+
+            let mut area_object = AreaObject::from_polygon(
+                Polygon::new(exterior, interiors),
+                symbol,
+                pattern_rotation,
+            );
+            area_object.tags = tags;
+            vec![MapObject::AreaObject(area_object)]
+        }
+        Symbol::Line(symbol) => to_line_strings(&coords, georef)
+            .into_iter()
+            .map(|line| {
+                let mut line_object = LineObject::from_line_string(line, symbol);
+                line_object.tags = tags.clone();
+                MapObject::LineObject(line_object)
+            })
+            .collect(),
+        Symbol::Point(symbol) => {
+            let point = Point(georef.to_projected(coords[0].0));
+            let mut point_object = PointObject::from_point(point, symbol, rotation);
+            point_object.tags = tags;
+            vec![MapObject::PointObject(point_object)]
+        }
+        Symbol::Text(symbol) => {
+            let point = Point(georef.to_projected(coords[0].0));
+            let mut text_object = TextObject::from_point(point, symbol, text);
+            // text without a rotation is upright on the map
+            text_object.rotation = raw_rotation
+                .filter(|r| *r != 0.)
+                .map(|r| r - georef.grivation);
+            text_object.tags = tags;
+            vec![MapObject::TextObject(text_object)]
+        }
+    };
+    Ok(Some(objects))
+}
+
+fn parse_coords(s: &str) -> OmapResult<Vec<(Coord, u8)>> {
+    let mut coords = Vec::new();
+    for c in s.split(';') {
+        let mut parts = c.split_whitespace();
+        let (Some(x), Some(y)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let flags = parts.next().unwrap_or("0");
+
+        let (Ok(x), Ok(y), Ok(flags)) = (x.parse::<i32>(), y.parse::<i32>(), flags.parse::<u8>())
+        else {
+            return Err(OmapError::InvalidFile(format!(
+                "could not parse coordinate \"{c}\""
+            )));
+        };
+        coords.push((
+            Coord {
+                x: x as f64,
+                y: y as f64,
+            },
+            flags,
+        ));
+    }
+    Ok(coords)
+}
+
+/// Split the flagged map coordinates into its parts, flatten the bezier curves and transform to projected coordinates
+fn to_line_strings(coords: &[(Coord, u8)], georef: &Georeferencing) -> Vec<LineString> {
+    let mut parts = Vec::new();
+    let mut part = Vec::new();
+
+    let mut i = 0;
+    // the end point of a curve is already pushed, but may start the next curve
+    let mut at_curve_end = false;
+    while i < coords.len() {
+        let (c, flags) = coords[i];
+        if !at_curve_end {
+            part.push(c);
+        }
+        at_curve_end = false;
+
+        if flags & CURVE_START != 0 && i + 3 < coords.len() {
+            flatten_bezier(
+                c,
+                coords[i + 1].0,
+                coords[i + 2].0,
+                coords[i + 3].0,
+                &mut part,
+            );
+            i += 3;
+            at_curve_end = true;
+        }
+
+        let end_flags = coords[i].1;
+        if end_flags & HOLE_POINT != 0 || i == coords.len() - 1 {
+            let mut line =
+                LineString::new(part.drain(..).map(|c| georef.to_projected(c)).collect());
+            if end_flags & CLOSE_POINT != 0 {
+                line.close();
+            }
+            parts.push(line);
+            at_curve_end = false;
+        }
+        if !at_curve_end {
+            i += 1;
+        }
+    }
+    parts
+}
+
+/// Push the points of the cubic bezier curve to `out`, excluding the start point
+fn flatten_bezier(p0: Coord, p1: Coord, p2: Coord, p3: Coord, out: &mut Vec<Coord>) {
+    // distance of the handles from the chord
+    let chord = p3 - p0;
+    let chord_length = chord.x.hypot(chord.y);
+    let deviation = if chord_length > f64::EPSILON {
+        let d1 = ((p1 - p0).x * chord.y - (p1 - p0).y * chord.x).abs();
+        let d2 = ((p2 - p0).x * chord.y - (p2 - p0).y * chord.x).abs();
+        d1.max(d2) / chord_length
+    } else {
+        (p1 - p0)
+            .x
+            .hypot((p1 - p0).y)
+            .max((p2 - p0).x.hypot((p2 - p0).y))
+    };
+
+    if deviation <= FLATTENING_TOLERANCE {
+        out.push(p3);
+        return;
+    }
+
+    // de Casteljau subdivision at t = 0.5
+    let p01 = (p0 + p1) / 2.;
+    let p12 = (p1 + p2) / 2.;
+    let p23 = (p2 + p3) / 2.;
+    let p012 = (p01 + p12) / 2.;
+    let p123 = (p12 + p23) / 2.;
+    let mid = (p012 + p123) / 2.;
+
+    flatten_bezier(p0, p01, p012, mid, out);
+    flatten_bezier(mid, p123, p23, p3, out);
+}
@@ -0,0 +1,109 @@
+use geo_types::{Coord, Rect};
+use proj4rs::{transform::transform, Proj};
+
+use crate::{
+    coordinate_mode::ToRelative, map_part::MapPart, CoordinateMode, Crs, Omap, OmapResult,
+};
+
+/// The deviation of the grid scale factor and the meridian convergence over the extent of a map from their values at the ref point
+///
+/// The map is written with the values at the ref point, so the deviations are the errors in scale and north direction at the edges of the map.
+/// Large deviations indicate that the map should be split or that the ref point should be moved
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistortionReport {
+    /// the sampled extent in projected coordinates
+    pub extent: Rect,
+    /// the grid scale factor of the projection at the ref point
+    pub ref_scale_factor: f64,
+    /// the meridian convergence at the ref point in radians
+    pub ref_convergence: f64,
+    /// the largest absolute deviation of the grid scale factor from the value at the ref point
+    pub max_scale_factor_deviation: f64,
+    /// the projected coordinate of the largest scale factor deviation
+    pub max_scale_factor_deviation_at: Coord,
+    /// the largest absolute deviation of the convergence from the value at the ref point in radians
+    pub max_convergence_deviation: f64,
+    /// the projected coordinate of the largest convergence deviation
+    pub max_convergence_deviation_at: Coord,
+}
+
+/// Sample the distortion over the extent of the parts, see [Omap::distortion_report](crate::Omap::distortion_report)
+pub(crate) fn distortion_report(
+    parts: &[MapPart],
+    crs: &Crs,
+    coordinate_mode: CoordinateMode,
+    ref_point: Coord,
+    samples_per_axis: usize,
+) -> OmapResult<DistortionReport> {
+    let local_proj = crs.to_proj()?;
+    let geo_proj = Proj::from_epsg_code(4326)?;
+    let to_relative = ToRelative::new(coordinate_mode, ref_point, Some(crs))?;
+
+    // the extent of the objects relative the ref point
+    let mut min = Coord {
+        x: f64::INFINITY,
+        y: f64::INFINITY,
+    };
+    let mut max = Coord {
+        x: f64::NEG_INFINITY,
+        y: f64::NEG_INFINITY,
+    };
+    let mut result = Ok(());
+    for obj in parts.iter().flat_map(|p| p.objects.values().flatten()) {
+        obj.for_each_coord(|mut c| {
+            if let Err(e) = to_relative.apply_coord(&mut c) {
+                result = Err(e);
+            }
+            min.x = min.x.min(c.x);
+            min.y = min.y.min(c.y);
+            max.x = max.x.max(c.x);
+            max.y = max.y.max(c.y);
+        });
+    }
+    result?;
+    let extent = if min.x <= max.x && min.y <= max.y {
+        Rect::new(min + ref_point, max + ref_point)
+    } else {
+        Rect::new(ref_point, ref_point)
+    };
+
+    let convergence_and_scale_factor = |c: Coord| -> OmapResult<(f64, f64)> {
+        let mut geo_point = c;
+        transform(&local_proj, &geo_proj, &mut geo_point)?;
+        Omap::get_convergence_and_grid_scale_factor(&local_proj, geo_point)
+    };
+    let (ref_convergence, ref_scale_factor) = convergence_and_scale_factor(ref_point)?;
+
+    let mut report = DistortionReport {
+        extent,
+        ref_scale_factor,
+        ref_convergence,
+        max_scale_factor_deviation: 0.,
+        max_scale_factor_deviation_at: ref_point,
+        max_convergence_deviation: 0.,
+        max_convergence_deviation_at: ref_point,
+    };
+    let steps = samples_per_axis.max(2) - 1;
+    for i in 0..=steps {
+        for j in 0..=steps {
+            let c = extent.min()
+                + Coord {
+                    x: extent.width() * i as f64 / steps as f64,
+                    y: extent.height() * j as f64 / steps as f64,
+                };
+            let (convergence, scale_factor) = convergence_and_scale_factor(c)?;
+
+            let scale_factor_deviation = (scale_factor - ref_scale_factor).abs();
+            if scale_factor_deviation > report.max_scale_factor_deviation {
+                report.max_scale_factor_deviation = scale_factor_deviation;
+                report.max_scale_factor_deviation_at = c;
+            }
+            let convergence_deviation = (convergence - ref_convergence).abs();
+            if convergence_deviation > report.max_convergence_deviation {
+                report.max_convergence_deviation = convergence_deviation;
+                report.max_convergence_deviation_at = c;
+            }
+        }
+    }
+    Ok(report)
+}
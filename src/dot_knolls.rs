@@ -0,0 +1,132 @@
+use geo_types::Point;
+
+use crate::{
+    contour_loop::ContourLoop,
+    coordinate_mode::ToRelative,
+    geometry::{line_string_aspect_midpoint_rotation, line_string_signed_area, measured_object},
+    map_part::MapPart,
+    objects::{MapObject, PointObject},
+    symbols::{LineSymbol, PointSymbol, SprintLineSymbol, SprintPointSymbol, Symbol},
+    OmapResult,
+};
+
+// the dot knoll, elongated dot knoll and depression symbols of a contour symbol
+struct KnollSymbols {
+    contour: LineSymbol,
+    dot_knoll: PointSymbol,
+    elongated_dot_knoll: PointSymbol,
+    depression: PointSymbol,
+}
+
+/// Turn the small contour loops of the parts to dotknolls and depressions, see [Omap::make_dotknolls_and_depressions](crate::Omap::make_dotknolls_and_depressions)
+pub(crate) fn make_dotknolls_and_depressions(
+    parts: &mut [MapPart],
+    to_relative: &ToRelative,
+    min_area: f64,
+    max_area: f64,
+    elongated_aspect: f64,
+    min_knoll_height: f64,
+    min_depression_depth: f64,
+) -> OmapResult<()> {
+    let isom = |contour| KnollSymbols {
+        contour,
+        dot_knoll: PointSymbol::DotKnoll,
+        elongated_dot_knoll: PointSymbol::ElongatedDotKnoll,
+        depression: PointSymbol::UDepression,
+    };
+    let sprint = |contour| KnollSymbols {
+        contour: LineSymbol::Sprint(contour),
+        dot_knoll: PointSymbol::Sprint(SprintPointSymbol::DotKnoll),
+        elongated_dot_knoll: PointSymbol::Sprint(SprintPointSymbol::ElongatedDotKnoll),
+        depression: PointSymbol::Sprint(SprintPointSymbol::UDepression),
+    };
+    let keys = [
+        isom(LineSymbol::Contour),
+        isom(LineSymbol::FormLine),
+        isom(LineSymbol::IndexContour),
+        sprint(SprintLineSymbol::Contour),
+        sprint(SprintLineSymbol::FormLine),
+        sprint(SprintLineSymbol::IndexContour),
+    ];
+    let symbols = keys.each_ref().map(|k| Symbol::Line(k.contour));
+
+    for part in parts.iter_mut() {
+        // the candidates for enclosing the small loops
+        let loops = ContourLoop::collect(part, &symbols, to_relative)?;
+
+        for key in keys.iter() {
+            let Some(contours) = part.objects.get_mut(&Symbol::Line(key.contour)) else {
+                continue;
+            };
+
+            // the small loops with their measured line
+            let mut small_loops = Vec::with_capacity(contours.len());
+            let mut i = 0;
+            while i < contours.len() {
+                let contour_object = &contours[i];
+                if let MapObject::LineObject(o) = contour_object {
+                    if o.line.is_closed() {
+                        let measured = measured_object(to_relative, contour_object)?.into_owned();
+                        let area = match &measured {
+                            MapObject::LineObject(m) => line_string_signed_area(&m.line),
+                            _ => unreachable!("measuring a line object gives a line object"),
+                        };
+
+                        if area.abs() <= max_area {
+                            small_loops.push((contours.swap_remove(i), measured));
+                        } else {
+                            i += 1;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                } else {
+                    panic!("Non-line object under contour symbol in objects hashmap");
+                }
+            }
+
+            for (small_loop, measured) in small_loops {
+                let (MapObject::LineObject(o), MapObject::LineObject(m)) = (&small_loop, &measured)
+                else {
+                    continue;
+                };
+                let area = line_string_signed_area(&m.line);
+
+                // ignore too small loops
+                if area.abs() < min_area {
+                    continue;
+                }
+
+                // the height above the enclosing contour, negative for depressions
+                let relief = ContourLoop::relief(&loops, &measured);
+                let is_depression = match relief {
+                    Some(relief) => relief < 0.,
+                    None => area < 0.,
+                };
+                match relief {
+                    Some(relief) if relief > 0. && relief < min_knoll_height => continue,
+                    Some(relief) if relief < 0. && -relief < min_depression_depth => continue,
+                    _ => (),
+                }
+
+                // placed on the loop in the map's coordinates, shaped and rotated as measured
+                let (_, mid_point, _) = line_string_aspect_midpoint_rotation(&o.line);
+                let (aspect, _, rotation) = line_string_aspect_midpoint_rotation(&m.line);
+
+                let (symbol, rotation) = if is_depression {
+                    (key.depression, 0.)
+                } else if aspect < elongated_aspect {
+                    (key.dot_knoll, 0.)
+                } else {
+                    (key.elongated_dot_knoll, rotation)
+                };
+                part.add_object(MapObject::PointObject(PointObject::from_point(
+                    Point(mid_point),
+                    symbol,
+                    rotation,
+                )));
+            }
+        }
+    }
+    Ok(())
+}
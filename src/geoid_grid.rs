@@ -0,0 +1,277 @@
+use std::path::Path;
+
+use crate::{OmapError, OmapResult};
+
+// the value marking missing data in GTX files
+const GTX_NO_DATA: f32 = -88.8888;
+
+/// A grid of geoid undulations (the height of the geoid above the WGS84 ellipsoid) loaded from a local file
+///
+/// Used to convert orthometric heights (above sea level) to ellipsoidal heights, the undulations are bilinearly interpolated
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoidGrid {
+    // the latitude and longitude of the south west node in degrees
+    south: f64,
+    west: f64,
+    // the node spacing in degrees
+    lat_spacing: f64,
+    lon_spacing: f64,
+    rows: usize,
+    cols: usize,
+    // row major from south to north and west to east, NaN for missing data
+    undulations: Vec<f32>,
+}
+
+impl GeoidGrid {
+    /// Load a grid from a GTX file, the binary format used by PROJ and many national mapping agencies
+    pub fn from_gtx_file(path: impl AsRef<Path>) -> OmapResult<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_gtx(&bytes)
+    }
+
+    /// Load a grid from the content of a GTX file
+    pub fn from_gtx(bytes: &[u8]) -> OmapResult<Self> {
+        const HEADER_LEN: usize = 40;
+        if bytes.len() < HEADER_LEN {
+            return Err(OmapError::InvalidGeoidGrid(
+                "the GTX header is incomplete".to_string(),
+            ));
+        }
+        let f64_at = |i: usize| f64::from_be_bytes(bytes[i..i + 8].try_into().unwrap());
+        let i32_at = |i: usize| i32::from_be_bytes(bytes[i..i + 4].try_into().unwrap());
+
+        let (rows, cols) = match (usize::try_from(i32_at(32)), usize::try_from(i32_at(36))) {
+            (Ok(rows), Ok(cols)) => (rows, cols),
+            _ => {
+                return Err(OmapError::InvalidGeoidGrid(
+                    "negative grid size".to_string(),
+                ))
+            }
+        };
+        let len = rows
+            .checked_mul(cols)
+            .and_then(|n| n.checked_mul(4))
+            .and_then(|n| n.checked_add(HEADER_LEN));
+        if len != Some(bytes.len()) {
+            return Err(OmapError::InvalidGeoidGrid(format!(
+                "expected {rows} x {cols} values"
+            )));
+        }
+
+        let undulations = bytes[HEADER_LEN..]
+            .chunks_exact(4)
+            .map(|b| f32::from_be_bytes(b.try_into().unwrap()))
+            .map(|u| if u == GTX_NO_DATA { f32::NAN } else { u })
+            .collect();
+
+        Self::new(
+            f64_at(0),
+            f64_at(8),
+            f64_at(16),
+            f64_at(24),
+            rows,
+            cols,
+            undulations,
+        )
+    }
+
+    /// Load a grid from an ASCII file in the GRAVSOFT format
+    ///
+    /// The header holds `south north west east lat_spacing lon_spacing` in degrees,
+    /// followed by the undulations row by row from north to south, each row from west to east
+    pub fn from_ascii_file(path: impl AsRef<Path>) -> OmapResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_ascii(&content)
+    }
+
+    /// Load a grid from the content of an ASCII file in the GRAVSOFT format, see [Self::from_ascii_file]
+    pub fn from_ascii(content: &str) -> OmapResult<Self> {
+        let mut values = content.split_whitespace().map(|v| {
+            v.parse::<f64>()
+                .map_err(|_| OmapError::InvalidGeoidGrid(format!("invalid number {v}")))
+        });
+        let mut header = [0.; 6];
+        for h in header.iter_mut() {
+            *h = values.next().ok_or(OmapError::InvalidGeoidGrid(
+                "the header is incomplete".to_string(),
+            ))??;
+        }
+        let [south, north, west, east, lat_spacing, lon_spacing] = header;
+        if lat_spacing <= 0. || lon_spacing <= 0. {
+            return Err(OmapError::InvalidGeoidGrid(
+                "the node spacing must be positive".to_string(),
+            ));
+        }
+        // the casts saturate, so absurd extents are caught by the checked arithmetic
+        let rows = (((north - south) / lat_spacing).round() as usize).checked_add(1);
+        let cols = (((east - west) / lon_spacing).round() as usize).checked_add(1);
+        let (Some(rows), Some(cols), Some(len)) = (
+            rows,
+            cols,
+            rows.zip(cols)
+                .and_then(|(rows, cols)| rows.checked_mul(cols)),
+        ) else {
+            return Err(OmapError::InvalidGeoidGrid(
+                "the grid is too large".to_string(),
+            ));
+        };
+
+        let north_to_south = values
+            .map(|v| v.map(|v| v as f32))
+            .collect::<OmapResult<Vec<_>>>()?;
+        if north_to_south.len() != len {
+            return Err(OmapError::InvalidGeoidGrid(format!(
+                "expected {rows} x {cols} values"
+            )));
+        }
+        let undulations = north_to_south
+            .chunks_exact(cols)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+
+        Self::new(
+            south,
+            west,
+            lat_spacing,
+            lon_spacing,
+            rows,
+            cols,
+            undulations,
+        )
+    }
+
+    fn new(
+        south: f64,
+        west: f64,
+        lat_spacing: f64,
+        lon_spacing: f64,
+        rows: usize,
+        cols: usize,
+        undulations: Vec<f32>,
+    ) -> OmapResult<Self> {
+        if rows < 2 || cols < 2 || lat_spacing <= 0. || lon_spacing <= 0. {
+            return Err(OmapError::InvalidGeoidGrid(
+                "the grid must have at least 2 x 2 nodes with a positive spacing".to_string(),
+            ));
+        }
+        Ok(GeoidGrid {
+            south,
+            west,
+            lat_spacing,
+            lon_spacing,
+            rows,
+            cols,
+            undulations,
+        })
+    }
+
+    /// Get the geoid undulation in meters at a geographic coordinate in degrees
+    /// None if the coordinate is outside the grid or next to missing data
+    pub fn undulation(&self, lon: f64, lat: f64) -> Option<f64> {
+        let row = (lat - self.south) / self.lat_spacing;
+        // grids may use longitudes from 0 to 360
+        let col = ((lon - self.west).rem_euclid(360.)) / self.lon_spacing;
+
+        let max_row = (self.rows - 1) as f64;
+        let max_col = (self.cols - 1) as f64;
+        if !(0. ..=max_row).contains(&row) || !(0. ..=max_col).contains(&col) {
+            return None;
+        }
+
+        // the south west node of the cell, the last row and column belong to the cell before them
+        let r = (row.floor() as usize).min(self.rows - 2);
+        let c = (col.floor() as usize).min(self.cols - 2);
+        let (dr, dc) = (row - r as f64, col - c as f64);
+
+        let at = |r: usize, c: usize| self.undulations[r * self.cols + c] as f64;
+        let south = at(r, c) * (1. - dc) + at(r, c + 1) * dc;
+        let north = at(r + 1, c) * (1. - dc) + at(r + 1, c + 1) * dc;
+        let undulation = south * (1. - dr) + north * dr;
+
+        (!undulation.is_nan()).then_some(undulation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gtx(south: f64, west: f64, rows: i32, cols: i32, undulations: &[f32]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for v in [south, west, 1., 1.] {
+            bytes.extend(v.to_be_bytes());
+        }
+        bytes.extend(rows.to_be_bytes());
+        bytes.extend(cols.to_be_bytes());
+        for u in undulations {
+            bytes.extend(u.to_be_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_gtx_grids() {
+        let grid =
+            GeoidGrid::from_gtx(&gtx(59., 10., 2, 3, &[38., 40., 42., 39., 41., 43.])).unwrap();
+        assert_eq!(grid.undulation(10., 59.), Some(38.));
+        assert_eq!(grid.undulation(12., 60.), Some(43.));
+        assert_eq!(grid.undulation(10.5, 59.5), Some(39.5));
+        assert_eq!(grid.undulation(10., 60.5), None);
+
+        // longitudes from 0 to 360 and missing data
+        let grid =
+            GeoidGrid::from_gtx(&gtx(0., 359., 2, 3, &[1., 2., GTX_NO_DATA, 1., 2., 3.])).unwrap();
+        assert_eq!(grid.undulation(-0.5, 0.5), Some(1.5));
+        assert_eq!(grid.undulation(0.5, 0.5), None);
+    }
+
+    #[test]
+    fn rejects_gtx_with_wrong_sizes() {
+        let valid = gtx(59., 10., 2, 2, &[1., 2., 3., 4.]);
+        assert!(GeoidGrid::from_gtx(&valid).is_ok());
+        for bytes in [
+            valid[..36].to_vec(),
+            valid[..valid.len() - 4].to_vec(),
+            [valid.as_slice(), &[0; 4]].concat(),
+            gtx(59., 10., 3, 2, &[1., 2., 3., 4.]),
+            gtx(59., 10., -2, -2, &[1., 2., 3., 4.]),
+            gtx(59., 10., 1, 4, &[1., 2., 3., 4.]),
+        ] {
+            assert!(matches!(
+                GeoidGrid::from_gtx(&bytes),
+                Err(OmapError::InvalidGeoidGrid(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_gtx_headers() {
+        let mut bytes = vec![0; 40];
+        bytes[32..36].copy_from_slice(&i32::MAX.to_be_bytes());
+        bytes[36..40].copy_from_slice(&i32::MAX.to_be_bytes());
+        assert!(matches!(
+            GeoidGrid::from_gtx(&bytes),
+            Err(OmapError::InvalidGeoidGrid(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_oversized_ascii_headers() {
+        for header in ["-90 90 0 360 1e-300 1e-300 0", "0 1e300 0 1e300 1 1 0"] {
+            assert!(matches!(
+                GeoidGrid::from_ascii(header),
+                Err(OmapError::InvalidGeoidGrid(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn interpolates_ascii_grids() {
+        let grid = GeoidGrid::from_ascii("50 51 10 12 1 1\n 3 4 5\n 1 2 3").unwrap();
+        assert_eq!(grid.undulation(10., 50.), Some(1.));
+        assert_eq!(grid.undulation(11.5, 50.5), Some(3.5));
+        assert_eq!(grid.undulation(13., 50.5), None);
+    }
+}
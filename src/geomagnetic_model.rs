@@ -0,0 +1,282 @@
+use std::path::Path;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::{OmapError, OmapResult};
+
+// WGS84 ellipsoid
+const A: f64 = 6378137.;
+const F: f64 = 1. / 298.257223563;
+// the reference radius of the spherical harmonic expansion
+const REFERENCE_RADIUS: f64 = 6371200.;
+// the validity of a WMM model from its epoch in years
+const WMM_VALIDITY: f64 = 5.;
+
+/// A spherical harmonic model of the main geomagnetic field loaded from a coefficient file
+///
+/// Reads the COF format of the WMM and WMMHR (one model with its secular variation valid for 5 years from the epoch)
+/// and the multi-model COF format of the IGRF used by geomag70 (models between their epochs are interpolated linearly)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeomagneticModel {
+    models: Vec<CoefficientSet>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CoefficientSet {
+    epoch: f64,
+    valid_from: f64,
+    valid_until: f64,
+    // (g, h, g_dot, h_dot) in nT and nT/year indexed by `index(n, m)`
+    coefficients: Vec<[f64; 4]>,
+}
+
+impl GeomagneticModel {
+    /// Load a model from a coefficient file, e.g. WMM.COF, WMMHR.COF or IGRF14.COF
+    pub fn from_cof_file(path: impl AsRef<Path>) -> OmapResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_cof(&content)
+    }
+
+    /// Load a model from the content of a coefficient file, see [Self::from_cof_file]
+    pub fn from_cof(content: &str) -> OmapResult<Self> {
+        let invalid = |msg: String| OmapError::InvalidGeomagneticModel(msg);
+
+        let mut models: Vec<CoefficientSet> = Vec::new();
+        // the coefficients given for each model, all of them up to its degree must be given
+        let mut given: Vec<Vec<bool>> = Vec::new();
+        let mut in_model = false;
+        for line in content.lines() {
+            let tokens = line.split_whitespace().collect::<Vec<_>>();
+            if tokens.is_empty() {
+                continue;
+            }
+            // the WMM files end each model with a line of nines
+            if tokens[0].starts_with("9999") {
+                in_model = false;
+                continue;
+            }
+
+            let degree_order = (tokens[0].parse::<usize>(), tokens.get(1).map(|t| t.parse()));
+            if let (Ok(n), Some(Ok(m))) = degree_order {
+                if !in_model {
+                    return Err(invalid(format!("coefficients before a header: {line}")));
+                }
+                if n == 0 || m > n || tokens.len() < 6 {
+                    return Err(invalid(format!("invalid coefficient line: {line}")));
+                }
+                let mut values = [0.; 4];
+                for (v, t) in values.iter_mut().zip(&tokens[2..6]) {
+                    *v = t
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid number {t}")))?;
+                }
+                let coefficients = &mut models.last_mut().unwrap().coefficients;
+                let given = given.last_mut().unwrap();
+                let i = index(n, m);
+                if coefficients.len() <= i {
+                    coefficients.resize(i + 1, [0.; 4]);
+                    given.resize(i + 1, false);
+                }
+                coefficients[i] = values;
+                given[i] = true;
+                continue;
+            }
+
+            let number = |i: usize| {
+                tokens
+                    .get(i)
+                    .and_then(|t| t.parse::<f64>().ok())
+                    .ok_or_else(|| invalid(format!("invalid header: {line}")))
+            };
+            let set = if let Ok(epoch) = tokens[0].parse::<f64>() {
+                // WMM header: epoch, model name and release date
+                CoefficientSet {
+                    epoch,
+                    valid_from: epoch,
+                    valid_until: epoch + WMM_VALIDITY,
+                    coefficients: Vec::new(),
+                }
+            } else {
+                // geomag70 header: model name, epoch, max degrees and the validity in years
+                CoefficientSet {
+                    epoch: number(1)?,
+                    valid_from: number(5)?,
+                    valid_until: number(6)?,
+                    coefficients: Vec::new(),
+                }
+            };
+            models.push(set);
+            given.push(Vec::new());
+            in_model = true;
+        }
+
+        if models.is_empty() || models.iter().any(|m| m.coefficients.is_empty()) {
+            return Err(invalid("no model coefficients".to_string()));
+        }
+        for (model, given) in models.iter().zip(given.iter()) {
+            let max_degree = degree(model.coefficients.len() - 1);
+            let missing = (1..=max_degree)
+                .flat_map(|n| (0..=n).map(move |m| (n, m)))
+                .find(|&(n, m)| given.get(index(n, m)) != Some(&true));
+            if let Some((n, m)) = missing {
+                return Err(invalid(format!(
+                    "the coefficient of degree {n} and order {m} is missing in the model of {}",
+                    model.epoch
+                )));
+            }
+        }
+        models.sort_by(|a, b| a.epoch.total_cmp(&b.epoch));
+        Ok(GeomagneticModel { models })
+    }
+
+    /// The first and last (decimal) year the model is valid for
+    pub fn validity(&self) -> (f64, f64) {
+        (
+            self.models.first().unwrap().valid_from,
+            self.models.last().unwrap().valid_until,
+        )
+    }
+
+    /// Get the magnetic declination in radians at a geographic coordinate in degrees, `meters_above_ellipsoid` in elevation, on the given date
+    pub fn declination(
+        &self,
+        lon: f64,
+        lat: f64,
+        meters_above_ellipsoid: f64,
+        date: NaiveDate,
+    ) -> OmapResult<f64> {
+        let coefficients = self.coefficients_at(date)?;
+
+        // geodetic to geocentric spherical coordinates
+        let (lat, lon) = (lat.to_radians(), lon.to_radians());
+        let e2 = F * (2. - F);
+        let prime_vertical_radius = A / (1. - e2 * lat.sin().powi(2)).sqrt();
+        let p = (prime_vertical_radius + meters_above_ellipsoid) * lat.cos();
+        let z = (prime_vertical_radius * (1. - e2) + meters_above_ellipsoid) * lat.sin();
+        let r = p.hypot(z);
+        let geocentric_lat = (z / r).asin();
+
+        // the colatitude, the sine is kept away from 0 at the poles where the declination is undefined
+        let cos_theta = geocentric_lat.sin();
+        let sin_theta = geocentric_lat.cos().max(1e-10);
+
+        let max_degree = degree(coefficients.len() - 1);
+        let (p, dp) = schmidt_legendre(max_degree, cos_theta, sin_theta);
+
+        let (mut north, mut east, mut down) = (0., 0., 0.);
+        let mut radius_ratio = (REFERENCE_RADIUS / r).powi(2);
+        for n in 1..=max_degree {
+            radius_ratio *= REFERENCE_RADIUS / r;
+            for m in 0..=n {
+                let i = index(n, m);
+                let [g, h] = [coefficients[i][0], coefficients[i][1]];
+                let (sin_ml, cos_ml) = (m as f64 * lon).sin_cos();
+
+                north += radius_ratio * (g * cos_ml + h * sin_ml) * dp[i];
+                east += radius_ratio * m as f64 * (g * sin_ml - h * cos_ml) * p[i] / sin_theta;
+                down -= radius_ratio * (n + 1) as f64 * (g * cos_ml + h * sin_ml) * p[i];
+            }
+        }
+
+        // rotate the north component from the geocentric to the geodetic frame
+        let north = north * (geocentric_lat - lat).cos() - down * (geocentric_lat - lat).sin();
+        Ok(east.atan2(north))
+    }
+
+    // the Gauss coefficients (g, h) at the date
+    fn coefficients_at(&self, date: NaiveDate) -> OmapResult<Vec<[f64; 2]>> {
+        let days_in_year = if date.leap_year() { 366. } else { 365. };
+        let year = date.year() as f64 + date.ordinal0() as f64 / days_in_year;
+
+        let (valid_from, valid_until) = self.validity();
+        let i = self
+            .models
+            .iter()
+            .position(|m| (m.valid_from..m.valid_until).contains(&year))
+            .or_else(|| (year == valid_until).then_some(self.models.len() - 1))
+            .ok_or(OmapError::DateOutsideOfModelEpoch(
+                date,
+                valid_from,
+                valid_until,
+            ))?;
+        let model = &self.models[i];
+        let dt = year - model.epoch;
+
+        let has_secular_variation = model.coefficients.iter().any(|c| c[2] != 0. || c[3] != 0.);
+        match self.models.get(i + 1) {
+            // interpolate between definitive models without secular variation
+            Some(next) if !has_secular_variation => {
+                let t = dt / (next.epoch - model.epoch);
+                let len = model.coefficients.len().max(next.coefficients.len());
+                let at = |c: &[[f64; 4]], i: usize| c.get(i).copied().unwrap_or([0.; 4]);
+                Ok((0..len)
+                    .map(|i| {
+                        let (c0, c1) = (at(&model.coefficients, i), at(&next.coefficients, i));
+                        [c0[0] + (c1[0] - c0[0]) * t, c0[1] + (c1[1] - c0[1]) * t]
+                    })
+                    .collect())
+            }
+            _ => Ok(model
+                .coefficients
+                .iter()
+                .map(|c| [c[0] + c[2] * dt, c[1] + c[3] * dt])
+                .collect()),
+        }
+    }
+}
+
+// the index of the coefficient of degree n >= 1 and order m
+fn index(n: usize, m: usize) -> usize {
+    n * (n + 1) / 2 + m - 1
+}
+
+// the degree of the coefficient at the index
+fn degree(i: usize) -> usize {
+    let mut n = 1;
+    while index(n + 1, 0) <= i {
+        n += 1;
+    }
+    n
+}
+
+// the Schmidt semi-normalized associated Legendre functions and their derivatives by the colatitude
+fn schmidt_legendre(max_degree: usize, cos_theta: f64, sin_theta: f64) -> (Vec<f64>, Vec<f64>) {
+    let len = index(max_degree, max_degree) + 1;
+    let mut p = vec![0.; len];
+    let mut dp = vec![0.; len];
+    // P_0^0 is 1 with a derivative of 0 and not stored
+    let get = |v: &[f64], n: usize, m: usize, degree_0: f64| match n {
+        0 => degree_0,
+        _ if m > n => 0.,
+        _ => v[index(n, m)],
+    };
+
+    for n in 1..=max_degree {
+        for m in 0..=n {
+            let i = index(n, m);
+            if n == m {
+                let k = if n == 1 {
+                    1.
+                } else {
+                    (1. - 0.5 / n as f64).sqrt()
+                };
+                let (p1, dp1) = (get(&p, n - 1, n - 1, 1.), get(&dp, n - 1, n - 1, 0.));
+                p[i] = k * sin_theta * p1;
+                dp[i] = k * (cos_theta * p1 + sin_theta * dp1);
+            } else {
+                let (nf, mf) = (n as f64, m as f64);
+                let k = ((nf - 1.).powi(2) - mf * mf).sqrt();
+                let (p1, dp1) = (get(&p, n - 1, m, 1.), get(&dp, n - 1, m, 0.));
+                let (p2, dp2) = if n >= 2 {
+                    (get(&p, n - 2, m, 1.), get(&dp, n - 2, m, 0.))
+                } else {
+                    (0., 0.)
+                };
+                let norm = (nf * nf - mf * mf).sqrt();
+                p[i] = ((2. * nf - 1.) * cos_theta * p1 - k * p2) / norm;
+                dp[i] = ((2. * nf - 1.) * (cos_theta * dp1 - sin_theta * p1) - k * dp2) / norm;
+            }
+        }
+    }
+    (p, dp)
+}
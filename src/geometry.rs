@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+
+use geo_types::{Coord, LineString, Polygon};
+
+use crate::{coordinate_mode::ToRelative, objects::MapObject, OmapResult};
+
+// the object in projected coordinates relative the ref point
+pub(crate) fn measured_object<'a>(
+    to_relative: &ToRelative,
+    obj: &'a MapObject,
+) -> OmapResult<Cow<'a, MapObject>> {
+    if to_relative.is_identity() {
+        Ok(Cow::Borrowed(obj))
+    } else {
+        let mut obj = obj.clone();
+        to_relative.apply(&mut obj)?;
+        Ok(Cow::Owned(obj))
+    }
+}
+
+pub(crate) fn line_string_length(line: &LineString) -> f64 {
+    line.lines().map(|l| l.delta().x.hypot(l.delta().y)).sum()
+}
+
+pub(crate) fn polygon_area(polygon: &Polygon) -> f64 {
+    line_string_signed_area(polygon.exterior()).abs()
+        - polygon
+            .interiors()
+            .iter()
+            .map(|i| line_string_signed_area(i).abs())
+            .sum::<f64>()
+}
+
+pub(crate) fn line_string_signed_area(line: &LineString) -> f64 {
+    if line.0.len() < 3 {
+        return 0.;
+    }
+    let mut area: f64 = 0.;
+    for i in 0..line.0.len() - 1 {
+        area += line.0[i].x * line.0[i + 1].y - line.0[i].y * line.0[i + 1].x;
+    }
+    0.5 * area
+}
+
+pub(crate) fn line_string_aspect_midpoint_rotation(line: &LineString) -> (f64, Coord, f64) {
+    let mut midpoint = Coord::zero();
+    for c in line.0.iter() {
+        midpoint = midpoint + *c;
+    }
+    midpoint = midpoint / line.0.len() as f64;
+
+    // Calculate second moments
+    let mu20 = line
+        .0
+        .iter()
+        .map(|p| (p.x - midpoint.x).powi(2))
+        .sum::<f64>();
+    let mu02 = line
+        .0
+        .iter()
+        .map(|p| (p.y - midpoint.y).powi(2))
+        .sum::<f64>();
+    let mu11 = line
+        .0
+        .iter()
+        .map(|p| (p.x - midpoint.x) * (p.y - midpoint.y))
+        .sum::<f64>();
+
+    // Calculate elongation using eigenvalues of the covariance matrix
+    let temp = ((mu20 - mu02).powi(2) + 4.0 * mu11.powi(2)).sqrt();
+    let lambda1 = (mu20 + mu02 + temp) / 2.0;
+    let lambda2 = (mu20 + mu02 - temp) / 2.0;
+
+    // Handle potential numerical issues
+    if lambda2.abs() <= 2. * f64::EPSILON
+        || ((mu20 - mu02).abs() <= 2. * f64::EPSILON && mu11.abs() <= 2. * f64::EPSILON)
+    {
+        return (1., midpoint, 0.);
+    }
+
+    let elongation = (lambda1 / lambda2).sqrt();
+
+    // Calculate the angle of the major axis (in radians)
+    // The eigenvector corresponding to the largest eigenvalue gives the direction
+    let mut angle = 0.5 * f64::atan2(2.0 * mu11, mu20 - mu02);
+
+    // Ensure the angle corresponds to the major (not minor) axis
+    if !(mu20 < mu02 || mu11 >= 0.0) {
+        angle += std::f64::consts::FRAC_PI_2;
+    }
+
+    angle %= std::f64::consts::PI;
+    if angle < 0.0 {
+        angle += std::f64::consts::PI;
+    }
+
+    (elongation, midpoint, angle)
+}
+
+// the mean and the angle of the major axis in [0, PI) of the coordinates from their second moments
+pub(crate) fn coords_midpoint_major_axis(coords: &[Coord]) -> (Coord, f64) {
+    let mut midpoint = Coord::zero();
+    for c in coords.iter() {
+        midpoint = midpoint + *c;
+    }
+    midpoint = midpoint / coords.len() as f64;
+
+    let mu20 = coords
+        .iter()
+        .map(|p| (p.x - midpoint.x).powi(2))
+        .sum::<f64>();
+    let mu02 = coords
+        .iter()
+        .map(|p| (p.y - midpoint.y).powi(2))
+        .sum::<f64>();
+    let mu11 = coords
+        .iter()
+        .map(|p| (p.x - midpoint.x) * (p.y - midpoint.y))
+        .sum::<f64>();
+
+    // no direction stands out
+    if (mu20 - mu02).abs() <= 2. * f64::EPSILON && mu11.abs() <= 2. * f64::EPSILON {
+        return (midpoint, 0.);
+    }
+
+    // the direction of the eigenvector of the largest eigenvalue of the covariance matrix
+    let angle = (0.5 * f64::atan2(2.0 * mu11, mu20 - mu02)).rem_euclid(std::f64::consts::PI);
+    (midpoint, angle)
+}
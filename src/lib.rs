@@ -1,4 +1,4 @@
-//! Write Open Orienteering Mapper's .omap files in Rust
+//! Read and write Open Orienteering Mapper's .omap files in Rust
 //!
 //! # Example
 //!
@@ -6,7 +6,7 @@
 //! use omap::{
 //!     objects::{AreaObject, LineObject, PointObject, TextObject, TagTrait},
 //!     symbols::{AreaSymbol, LineSymbol, PointSymbol, TextSymbol},
-//!     Omap, Scale,
+//!     Crs, Omap, Scale,
 //!     };
 //! use geo_types::{Coord, LineString, Polygon, Point};
 //! use std::{path::PathBuf, str::FromStr};
@@ -18,7 +18,7 @@
 //! let mut omap = Omap::new(
 //!     map_center,
 //!     Scale::S15_000,
-//!     Some(crs_epsg_code),
+//!     Some(Crs::Epsg(crs_epsg_code)),
 //!     Some(map_center_elevation_meters)
 //! ).expect("Could not make map with the given CRS-code");
 //!
@@ -99,16 +99,51 @@
     warnings
 )]
 
+mod contour_assignment;
+mod contour_labels;
+mod contour_loop;
+mod coordinate_mode;
+mod crs;
+mod deserialize;
+#[cfg(feature = "geo_ref")]
+mod distortion;
+mod dot_knolls;
+#[cfg(feature = "geo_ref")]
+mod geoid_grid;
+#[cfg(feature = "geo_ref")]
+mod geomagnetic_model;
+mod geometry;
+mod map_part;
+mod min_size;
 /// Objects module
 pub mod objects;
 mod omap;
+mod omap_builder;
+#[cfg(feature = "geo_ref")]
+mod reprojection;
 mod scale;
 mod serialize;
+mod slope_lines;
 /// Symbols module
 pub mod symbols;
+mod update;
+mod xml_writer;
 
-pub use self::omap::Omap;
+pub use self::contour_assignment::ContourAssignmentReport;
+pub use self::coordinate_mode::CoordinateMode;
+pub use self::crs::Crs;
+#[cfg(feature = "geo_ref")]
+pub use self::distortion::DistortionReport;
+#[cfg(feature = "geo_ref")]
+pub use self::geoid_grid::GeoidGrid;
+#[cfg(feature = "geo_ref")]
+pub use self::geomagnetic_model::GeomagneticModel;
+pub use self::map_part::MapPart;
+pub use self::min_size::{CollapseRule, MinSizePolicy, MinSizeReport};
+pub use self::omap::{ObjectOrder, Omap};
+pub use self::omap_builder::OmapBuilder;
 pub use self::scale::Scale;
+pub use self::update::ReplaceFilter;
 
 /// crate result
 pub type OmapResult<T> = Result<T, OmapError>;
@@ -126,6 +161,24 @@ pub enum OmapError {
     /// IO error
     #[error(transparent)]
     IO(#[from] std::io::Error),
+    /// XML parsing error
+    #[error(transparent)]
+    Xml(#[from] quick_xml::Error),
+    /// The CRS of the map does not match the CRS of the file it updates
+    #[error("The map has CRS {0}, but the file has CRS {1}")]
+    MismatchedCrs(Crs, Crs),
+    /// The CRS can not be used for georeferencing computations
+    #[error("The CRS {0} can not be used for georeferencing computations")]
+    UnsupportedCrs(Crs),
+    /// A symbol of the map is not part of the symbol set written to the file
+    #[error("The symbol {0} is not in the symbol set {1}")]
+    SymbolNotInSymbolSet(symbols::Symbol, String),
+    /// The map needs a CRS for the operation, but has none
+    #[error("The map has no CRS")]
+    MissingCrs,
+    /// The file could not be read as an omap file
+    #[error("Invalid omap file: {0}")]
+    InvalidFile(String),
     /// Projection error
     #[cfg(feature = "geo_ref")]
     #[error(transparent)]
@@ -134,6 +187,22 @@ pub enum OmapError {
     #[cfg(feature = "geo_ref")]
     #[error(transparent)]
     GeoMagnetic(#[from] world_magnetic_model::Error),
+    /// The geomagnetic coefficient file could not be read
+    #[cfg(feature = "geo_ref")]
+    #[error("Invalid geomagnetic model: {0}")]
+    InvalidGeomagneticModel(String),
+    /// The geoid grid file could not be read
+    #[cfg(feature = "geo_ref")]
+    #[error("Invalid geoid grid: {0}")]
+    InvalidGeoidGrid(String),
+    /// The ref point of the map is outside the geoid grid or next to missing data
+    #[cfg(feature = "geo_ref")]
+    #[error("The ref point is not covered by the geoid grid")]
+    OutsideGeoidGrid,
+    /// The date of the declination is outside the epoch of the geomagnetic model
+    #[cfg(feature = "geo_ref")]
+    #[error("The date {0} is outside the epoch {1} to {2} of the geomagnetic model")]
+    DateOutsideOfModelEpoch(chrono::NaiveDate, f64, f64),
     /// The geo-referencing feature is de-activated, but an EPSG code was passed to new
     #[error("The geo-referencing feature is de-activated (activated by default)")]
     DisabledGeoReferencingFeature,
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use crate::{objects::MapObject, symbols::Symbol};
+
+/// A named part (layer) of a map, each part can be shown or hidden on its own in Mapper
+#[derive(Debug, Clone)]
+pub struct MapPart {
+    /// the name of the part
+    pub name: String,
+    /// the objects of the part
+    pub objects: HashMap<Symbol, Vec<MapObject>>,
+}
+
+impl MapPart {
+    /// Create a new empty part
+    pub fn new(name: impl Into<String>) -> Self {
+        MapPart {
+            name: name.into(),
+            objects: HashMap::new(),
+        }
+    }
+
+    /// Reserve capacity for `cap` elements for key `symbol` in the objects hashmap
+    pub fn reserve_capacity(&mut self, symbol: impl Into<Symbol>, cap: usize) {
+        let symbol = symbol.into();
+        if let Some(obj) = self.objects.get_mut(&symbol) {
+            obj.reserve(cap);
+        } else {
+            let _ = self.objects.insert(symbol, Vec::with_capacity(cap));
+        }
+    }
+
+    /// Insert an object in the objects hashmap
+    /// __All coordinates of objects added to the map must be relative the map's `ref_point`__
+    pub fn add_object(&mut self, obj: impl Into<MapObject>) {
+        let obj = obj.into();
+        let key = obj.symbol();
+        if let Some(val) = self.objects.get_mut(&key) {
+            val.push(obj);
+        } else {
+            let _ = self.objects.insert(key, vec![obj]);
+        }
+    }
+
+    /// Get the number of objects in the part
+    pub fn num_objects(&self) -> usize {
+        self.objects.values().fold(0, |acc, v| acc + v.len())
+    }
+}
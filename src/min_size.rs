@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+
+use geo_types::{Coord, Point};
+
+use crate::{
+    coordinate_mode::ToRelative,
+    geometry::{coords_midpoint_major_axis, line_string_length, measured_object, polygon_area},
+    map_part::MapPart,
+    objects::{MapObject, PointObject},
+    symbols::{
+        AreaSymbol, LineSymbol, PointSymbol, SprintAreaSymbol, SprintLineSymbol, Symbol,
+        SymbolTrait,
+    },
+    OmapResult, Scale,
+};
+
+/// What [Omap::enforce_min_sizes](crate::Omap::enforce_min_sizes) does with areas and lines below the minimum size of their symbol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MinSizePolicy {
+    /// Remove the objects
+    #[default]
+    Drop,
+    /// Scale the objects about their centre until they have the minimum size
+    Exaggerate,
+    /// Replace the objects by the minimum size point symbol of their symbol, e.g. a small marsh by the minimum marsh point.
+    /// Objects with symbols without a point symbol are removed
+    ConvertToPoint,
+}
+
+/// The objects changed by [Omap::enforce_min_sizes](crate::Omap::enforce_min_sizes), counted by their original symbol
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MinSizeReport {
+    /// the number of removed objects
+    pub dropped: HashMap<Symbol, usize>,
+    /// the number of objects scaled up to the minimum size
+    pub exaggerated: HashMap<Symbol, usize>,
+    /// the number of objects replaced by a point object
+    pub converted: HashMap<Symbol, usize>,
+}
+
+impl MinSizeReport {
+    /// The total number of changed objects
+    pub fn num_changed(&self) -> usize {
+        [&self.dropped, &self.exaggerated, &self.converted]
+            .iter()
+            .flat_map(|m| m.values())
+            .sum()
+    }
+}
+
+/// A rule of [Omap::collapse_to_points](crate::Omap::collapse_to_points): objects with the `symbol` smaller than `max_size` are replaced by a point object with the `point_symbol`
+///
+/// The size is the area in square meters for area objects and the length in meters for line objects
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollapseRule {
+    /// the symbol of the collapsed objects
+    pub symbol: Symbol,
+    /// the symbol of the point objects replacing them
+    pub point_symbol: PointSymbol,
+    /// the size below which objects are collapsed
+    pub max_size: f64,
+}
+
+impl CollapseRule {
+    /// Collapse objects below the minimum size of the symbol at the scale to its minimum size point symbol, e.g. a short cliff to a minimum cliff.
+    /// None if the symbol has no minimum size or point symbol
+    pub fn min_size(symbol: impl Into<Symbol>, scale: Scale) -> Option<Self> {
+        let symbol = symbol.into();
+        let point_symbol = match symbol {
+            Symbol::Area(a) => a.min_size_point_symbol(),
+            Symbol::Line(l) => l.min_size_point_symbol(),
+            Symbol::Point(_) | Symbol::Text(_) => None,
+        }?;
+        let max_size = symbol.min_size(scale);
+        (max_size > 0.).then_some(CollapseRule {
+            symbol,
+            point_symbol,
+            max_size,
+        })
+    }
+
+    /// The [Self::min_size] rules for the ISOM and ISSprOM symbols with a minimum size point symbol
+    pub fn min_size_defaults(scale: Scale) -> Vec<Self> {
+        let symbols: [Symbol; 20] = [
+            LineSymbol::Cliff.into(),
+            LineSymbol::ImpassableCliff.into(),
+            LineSymbol::CliffWithTags.into(),
+            LineSymbol::EarthBank.into(),
+            LineSymbol::NarrowMarsh.into(),
+            LineSymbol::BridgeTunnel.into(),
+            LineSymbol::Ruin.into(),
+            AreaSymbol::Building.into(),
+            AreaSymbol::Marsh.into(),
+            AreaSymbol::UncrossableMarshWithBankLine.into(),
+            AreaSymbol::UncrossableMarshWithoutBankLine.into(),
+            AreaSymbol::IndistinctMarsh.into(),
+            SprintLineSymbol::Cliff.into(),
+            SprintLineSymbol::ImpassableCliff.into(),
+            SprintLineSymbol::EarthBank.into(),
+            SprintLineSymbol::NarrowMarsh.into(),
+            SprintLineSymbol::BridgeTunnel.into(),
+            SprintLineSymbol::Ruin.into(),
+            SprintAreaSymbol::Building.into(),
+            SprintAreaSymbol::Marsh.into(),
+        ];
+        symbols
+            .into_iter()
+            .filter_map(|s| Self::min_size(s, scale))
+            .collect()
+    }
+}
+
+/// Enforce the minimum sizes in the parts, see [Omap::enforce_min_sizes](crate::Omap::enforce_min_sizes)
+pub(crate) fn enforce_min_sizes(
+    parts: &mut [MapPart],
+    to_relative: &ToRelative,
+    scale: Scale,
+    policy: MinSizePolicy,
+) -> OmapResult<MinSizeReport> {
+    let mut report = MinSizeReport::default();
+    for part in parts.iter_mut() {
+        let mut points = Vec::new();
+        for (symbol, objects) in part.objects.iter_mut() {
+            let min_size = symbol.min_size(scale);
+            if min_size <= 0. {
+                continue;
+            }
+
+            let mut kept = Vec::with_capacity(objects.len());
+            for mut obj in std::mem::take(objects) {
+                let measured = measured_object(to_relative, &obj)?;
+                let (size, scale_factor) = match measured.as_ref() {
+                    MapObject::LineObject(o) => {
+                        let length = line_string_length(&o.line);
+                        (length, min_size / length)
+                    }
+                    MapObject::AreaObject(o) => {
+                        let area = polygon_area(&o.polygon);
+                        (area, (min_size / area).sqrt())
+                    }
+                    MapObject::PointObject(_) | MapObject::TextObject(_) => {
+                        kept.push(obj);
+                        continue;
+                    }
+                };
+                // exaggerated objects in large absolute coordinates may end up a rounding error too small
+                if size >= min_size * (1. - 1e-9) {
+                    kept.push(obj);
+                    continue;
+                }
+
+                let point_symbol = match symbol {
+                    Symbol::Area(a) => a.min_size_point_symbol(),
+                    Symbol::Line(l) => l.min_size_point_symbol(),
+                    Symbol::Point(_) | Symbol::Text(_) => None,
+                };
+                match (policy, point_symbol) {
+                    (MinSizePolicy::Exaggerate, _) if scale_factor.is_finite() => {
+                        // scale about the centre of the bounding box
+                        let mut min = Coord {
+                            x: f64::INFINITY,
+                            y: f64::INFINITY,
+                        };
+                        let mut max = -min;
+                        obj.for_each_coord(|c| {
+                            min.x = min.x.min(c.x);
+                            min.y = min.y.min(c.y);
+                            max.x = max.x.max(c.x);
+                            max.y = max.y.max(c.y);
+                        });
+                        let centre = (min + max) / 2.;
+                        obj.try_for_each_coord(|c| {
+                            *c = centre + (*c - centre) * scale_factor;
+                            Ok(())
+                        })?;
+                        kept.push(obj);
+                        *report.exaggerated.entry(*symbol).or_default() += 1;
+                    }
+                    (MinSizePolicy::ConvertToPoint, Some(point_symbol)) => {
+                        let point = collapse_to_point(&obj, &measured, point_symbol);
+                        points.push(MapObject::PointObject(point));
+                        *report.converted.entry(*symbol).or_default() += 1;
+                    }
+                    _ => *report.dropped.entry(*symbol).or_default() += 1,
+                }
+            }
+            *objects = kept;
+        }
+        for point in points {
+            part.add_object(point);
+        }
+    }
+    Ok(report)
+}
+
+/// Collapse small objects in the parts to points, see [Omap::collapse_to_points](crate::Omap::collapse_to_points)
+pub(crate) fn collapse_to_points(
+    parts: &mut [MapPart],
+    to_relative: &ToRelative,
+    rules: &[CollapseRule],
+) -> OmapResult<usize> {
+    let mut num_collapsed = 0;
+    for part in parts.iter_mut() {
+        let mut points = Vec::new();
+        for rule in rules {
+            let Some(objects) = part.objects.get_mut(&rule.symbol) else {
+                continue;
+            };
+
+            let mut kept = Vec::with_capacity(objects.len());
+            for obj in std::mem::take(objects) {
+                let measured = measured_object(to_relative, &obj)?;
+                let size = match measured.as_ref() {
+                    MapObject::LineObject(o) => line_string_length(&o.line),
+                    MapObject::AreaObject(o) => polygon_area(&o.polygon),
+                    MapObject::PointObject(_) | MapObject::TextObject(_) => f64::INFINITY,
+                };
+                if size < rule.max_size {
+                    let point = collapse_to_point(&obj, &measured, rule.point_symbol);
+                    points.push(MapObject::PointObject(point));
+                } else {
+                    kept.push(obj);
+                }
+            }
+            *objects = kept;
+        }
+        num_collapsed += points.len();
+        for point in points {
+            part.add_object(point);
+        }
+    }
+    Ok(num_collapsed)
+}
+
+// a point object with the tags of a line or area object at its centroid, rotated along its major axis in the `measured` object
+fn collapse_to_point(
+    obj: &MapObject,
+    measured: &MapObject,
+    point_symbol: PointSymbol,
+) -> PointObject {
+    let (centroid, _) = coords_midpoint_major_axis(moment_coords(obj));
+    let (_, mut rotation) = coords_midpoint_major_axis(moment_coords(measured));
+
+    // the major axis is ambiguous, so points from lines are turned to the direction of the line
+    if let MapObject::LineObject(o) = measured {
+        if let (Some(&first), Some(&last)) = (o.line.0.first(), o.line.0.last()) {
+            let direction = last - first;
+            if direction.x * rotation.cos() + direction.y * rotation.sin() < 0. {
+                rotation += std::f64::consts::PI;
+            }
+        }
+    }
+
+    let mut point = PointObject::from_point(Point(centroid), point_symbol, rotation);
+    point.tags = obj.tags().clone();
+    point
+}
+
+// the vertices of a line or the exterior of an area without its closing point
+fn moment_coords(obj: &MapObject) -> &[Coord] {
+    match obj {
+        MapObject::LineObject(o) => &o.line.0,
+        MapObject::AreaObject(o) => {
+            let exterior = &o.polygon.exterior().0;
+            &exterior[..exterior.len().saturating_sub(1)]
+        }
+        MapObject::PointObject(o) => std::slice::from_ref(&o.point.0),
+        MapObject::TextObject(_) => &[],
+    }
+}
@@ -2,14 +2,11 @@ use crate::{
     objects::{MapObjectTrait, TagTrait},
     serialize::{SerializeBezier, SerializePolyLine},
     symbols::{AreaSymbol, SymbolTrait},
+    xml_writer::XmlWriter,
     OmapResult, Scale,
 };
 use geo_types::Polygon;
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufWriter, Write},
-};
+use std::{collections::HashMap, io::Write};
 
 /// A AreaObject representing anything that has a AreaSymbol
 #[derive(Debug, Clone)]
@@ -27,10 +24,14 @@ pub struct AreaObject {
 
 impl AreaObject {
     /// create an area object from a geo_types::Polygon
-    pub fn from_polygon(polygon: Polygon, symbol: AreaSymbol, pattern_rotation: f64) -> Self {
+    pub fn from_polygon(
+        polygon: Polygon,
+        symbol: impl Into<AreaSymbol>,
+        pattern_rotation: f64,
+    ) -> Self {
         Self {
             polygon,
-            symbol,
+            symbol: symbol.into(),
             pattern_rotation,
             tags: HashMap::new(),
         }
@@ -44,24 +45,25 @@ impl TagTrait for AreaObject {
 }
 
 impl MapObjectTrait for AreaObject {
-    fn write_to_map(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_to_map<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         bez_error: Option<f64>,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
     ) -> OmapResult<()> {
-        f.write_all(format!("<object type=\"1\" symbol=\"{}\">", self.symbol.id()).as_bytes())?;
+        f.start("object", &[("type", &1), ("symbol", &self.symbol.id())])?;
         self.write_tags(f)?;
         self.write_coords(f, bez_error, scale, grivation, inv_combined_scale_factor)?;
-        f.write_all(b"</object>\n")?;
+        f.end("object")?;
+        f.newline()?;
         Ok(())
     }
 
-    fn write_coords(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_coords<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         bez_error: Option<f64>,
         scale: Scale,
         grivation: f64,
@@ -74,31 +76,21 @@ impl MapObjectTrait for AreaObject {
             self.polygon
                 .serialize_polyline(scale, grivation, inv_combined_scale_factor)
         }?;
-        f.write_all(format!("<coords count=\"{num_coords}\">").as_bytes())?;
-        f.write_all(&bytes)?;
-        f.write_all(b"</coords>")?;
+        f.start("coords", &[("count", &num_coords)])?;
+        f.raw(&bytes)?;
+        f.end("coords")?;
         if self.symbol.is_rotatable() {
-            f.write_all(
-                format!(
-                    "<pattern rotation=\"{}\"><coord x=\"0\" y=\"0\"/></pattern>",
-                    self.pattern_rotation + grivation
-                )
-                .as_bytes(),
+            f.start(
+                "pattern",
+                &[("rotation", &(self.pattern_rotation + grivation))],
             )?;
+            f.empty("coord", &[("x", &0), ("y", &0)])?;
+            f.end("pattern")?;
         }
         Ok(())
     }
 
-    fn write_tags(&self, f: &mut BufWriter<File>) -> OmapResult<()> {
-        if self.tags.is_empty() {
-            return Ok(());
-        }
-
-        f.write_all(b"<tags>")?;
-        for (key, val) in self.tags.iter() {
-            f.write_all(format!("<t k=\"{key}\">{val}</t>").as_bytes())?;
-        }
-        f.write_all(b"</tags>")?;
-        Ok(())
+    fn tags(&self) -> &HashMap<String, String> {
+        &self.tags
     }
 }
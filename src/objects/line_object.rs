@@ -2,14 +2,11 @@ use crate::{
     objects::{MapObjectTrait, TagTrait},
     serialize::{SerializeBezier, SerializePolyLine},
     symbols::{LineSymbol, SymbolTrait},
+    xml_writer::XmlWriter,
     OmapResult, Scale,
 };
 use geo_types::LineString;
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufWriter, Write},
-};
+use std::{collections::HashMap, io::Write};
 
 /// A LineObject representing anything that has a LineSymbol
 #[derive(Debug, Clone)]
@@ -24,10 +21,10 @@ pub struct LineObject {
 
 impl LineObject {
     /// create a line object from a geo_types::LineString
-    pub fn from_line_string(line: LineString, symbol: LineSymbol) -> Self {
+    pub fn from_line_string(line: LineString, symbol: impl Into<LineSymbol>) -> Self {
         Self {
             line,
-            symbol,
+            symbol: symbol.into(),
             tags: HashMap::new(),
         }
     }
@@ -40,24 +37,25 @@ impl TagTrait for LineObject {
 }
 
 impl MapObjectTrait for LineObject {
-    fn write_to_map(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_to_map<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         bez_error: Option<f64>,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
     ) -> OmapResult<()> {
-        f.write_all(format!("<object type=\"1\" symbol=\"{}\">", self.symbol.id()).as_bytes())?;
+        f.start("object", &[("type", &1), ("symbol", &self.symbol.id())])?;
         self.write_tags(f)?;
         self.write_coords(f, bez_error, scale, grivation, inv_combined_scale_factor)?;
-        f.write_all(b"</object>\n")?;
+        f.end("object")?;
+        f.newline()?;
         Ok(())
     }
 
-    fn write_coords(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_coords<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         bez_error: Option<f64>,
         scale: Scale,
         grivation: f64,
@@ -70,22 +68,13 @@ impl MapObjectTrait for LineObject {
             self.line
                 .serialize_polyline(scale, grivation, inv_combined_scale_factor)
         }?;
-        f.write_all(format!("<coords count=\"{num_coords}\">").as_bytes())?;
-        f.write_all(&bytes)?;
-        f.write_all(b"</coords>")?;
+        f.start("coords", &[("count", &num_coords)])?;
+        f.raw(&bytes)?;
+        f.end("coords")?;
         Ok(())
     }
 
-    fn write_tags(&self, f: &mut BufWriter<File>) -> OmapResult<()> {
-        if self.tags.is_empty() {
-            return Ok(());
-        }
-
-        f.write_all(b"<tags>")?;
-        for (key, val) in self.tags.iter() {
-            f.write_all(format!("<t k=\"{key}\">{val}</t>").as_bytes())?;
-        }
-        f.write_all(b"</tags>")?;
-        Ok(())
+    fn tags(&self) -> &HashMap<String, String> {
+        &self.tags
     }
 }
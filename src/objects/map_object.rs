@@ -1,6 +1,7 @@
 use super::{AreaObject, LineObject, MapObjectTrait, PointObject, TagTrait, TextObject};
-use crate::{symbols::Symbol, OmapResult, Scale};
-use std::{fs::File, io::BufWriter};
+use crate::{symbols::Symbol, xml_writer::XmlWriter, OmapResult, Scale};
+use geo_types::Coord;
+use std::{collections::HashMap, io::Write};
 
 /// Enum for the different map object types
 #[derive(Debug, Clone)]
@@ -40,9 +41,9 @@ impl From<TextObject> for MapObject {
 }
 
 impl MapObject {
-    pub(crate) fn write_to_map(
-        self,
-        f: &mut BufWriter<File>,
+    pub(crate) fn write_to_map<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         bezier_error: Option<f64>,
         scale: Scale,
         grivation: f64,
@@ -80,6 +81,87 @@ impl MapObject {
         }
     }
 
+    /// translate the geometry of a map object by `offset`
+    pub(crate) fn translate(&mut self, offset: Coord) {
+        match self {
+            MapObject::LineObject(line_object) => {
+                line_object.line.0.iter_mut().for_each(|c| *c = *c + offset)
+            }
+            MapObject::PointObject(point_object) => {
+                point_object.point.0 = point_object.point.0 + offset
+            }
+            MapObject::AreaObject(area_object) => {
+                area_object
+                    .polygon
+                    .exterior_mut(|e| e.0.iter_mut().for_each(|c| *c = *c + offset));
+                area_object.polygon.interiors_mut(|interiors| {
+                    for i in interiors {
+                        i.0.iter_mut().for_each(|c| *c = *c + offset);
+                    }
+                });
+            }
+            MapObject::TextObject(text_object) => {
+                text_object.point.0 = text_object.point.0 + offset
+            }
+        }
+    }
+
+    /// apply a fallible function to every coordinate of the geometry of a map object
+    pub(crate) fn try_for_each_coord(
+        &mut self,
+        mut f: impl FnMut(&mut Coord) -> OmapResult<()>,
+    ) -> OmapResult<()> {
+        match self {
+            MapObject::LineObject(line_object) => line_object.line.0.iter_mut().try_for_each(f),
+            MapObject::PointObject(point_object) => f(&mut point_object.point.0),
+            MapObject::AreaObject(area_object) => {
+                let mut result = Ok(());
+                area_object
+                    .polygon
+                    .exterior_mut(|e| result = e.0.iter_mut().try_for_each(&mut f));
+                result?;
+                let mut result = Ok(());
+                area_object.polygon.interiors_mut(|interiors| {
+                    result = interiors
+                        .iter_mut()
+                        .try_for_each(|i| i.0.iter_mut().try_for_each(&mut f));
+                });
+                result
+            }
+            MapObject::TextObject(text_object) => f(&mut text_object.point.0),
+        }
+    }
+
+    /// apply a function to every coordinate of the geometry of a map object
+    pub(crate) fn for_each_coord(&self, mut f: impl FnMut(Coord)) {
+        match self {
+            MapObject::LineObject(line_object) => line_object.line.0.iter().for_each(|&c| f(c)),
+            MapObject::PointObject(point_object) => f(point_object.point.0),
+            MapObject::AreaObject(area_object) => {
+                area_object.polygon.exterior().0.iter().for_each(|&c| f(c));
+                for i in area_object.polygon.interiors() {
+                    i.0.iter().for_each(|&c| f(c));
+                }
+            }
+            MapObject::TextObject(text_object) => f(text_object.point.0),
+        }
+    }
+
+    /// get the tags of a map object
+    pub(crate) fn tags(&self) -> &HashMap<String, String> {
+        match self {
+            MapObject::LineObject(line_object) => &line_object.tags,
+            MapObject::PointObject(point_object) => &point_object.tags,
+            MapObject::AreaObject(area_object) => &area_object.tags,
+            MapObject::TextObject(text_object) => &text_object.tags,
+        }
+    }
+
+    /// get the value of the Elevation tag of a map object
+    pub(crate) fn elevation(&self) -> Option<f64> {
+        self.tags().get("Elevation")?.trim().parse().ok()
+    }
+
     /// get symbol of a map object
     pub fn symbol(&self) -> Symbol {
         match self {
@@ -1,5 +1,5 @@
-use crate::{OmapResult, Scale};
-use std::{fs::File, io::BufWriter};
+use crate::{xml_writer::XmlWriter, OmapResult, Scale};
+use std::{collections::HashMap, io::Write};
 
 mod area_object;
 mod line_object;
@@ -14,25 +14,42 @@ pub use point_object::PointObject;
 pub use text_object::TextObject;
 
 pub(crate) trait MapObjectTrait {
-    fn write_to_map(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_to_map<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         bezier_error: Option<f64>,
         scale: Scale,
         grivation: f64,
         combined_scale_factor: f64,
     ) -> OmapResult<()>;
 
-    fn write_coords(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_coords<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         bezier_error: Option<f64>,
         scale: Scale,
         grivation: f64,
         combined_scale_factor: f64,
     ) -> OmapResult<()>;
 
-    fn write_tags(&self, f: &mut BufWriter<File>) -> OmapResult<()>;
+    fn tags(&self) -> &HashMap<String, String>;
+
+    fn write_tags<W: Write>(&self, f: &mut XmlWriter<W>) -> OmapResult<()> {
+        if self.tags().is_empty() {
+            return Ok(());
+        }
+
+        // sorted for a deterministic output
+        let mut tags: Vec<_> = self.tags().iter().collect();
+        tags.sort();
+
+        f.start("tags", &[])?;
+        for (key, val) in tags {
+            f.element("t", &[("k", key)], val)?;
+        }
+        f.end("tags")?;
+        Ok(())
+    }
 }
 
 /// trait for adding tags to objects
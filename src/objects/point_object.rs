@@ -2,14 +2,11 @@ use crate::{
     objects::{MapObjectTrait, TagTrait},
     serialize::SerializePolyLine,
     symbols::{PointSymbol, SymbolTrait},
+    xml_writer::XmlWriter,
     OmapResult, Scale,
 };
 use geo_types::Point;
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufWriter, Write},
-};
+use std::{collections::HashMap, io::Write};
 
 /// A PointObject representing anything that has a PointSymbol
 #[derive(Debug, Clone)]
@@ -26,10 +23,10 @@ pub struct PointObject {
 
 impl PointObject {
     /// create a point object from a geo_types::Point
-    pub fn from_point(point: Point, symbol: PointSymbol, rotation: f64) -> Self {
+    pub fn from_point(point: Point, symbol: impl Into<PointSymbol>, rotation: f64) -> Self {
         Self {
             point,
-            symbol,
+            symbol: symbol.into(),
             rotation,
             tags: HashMap::new(),
         }
@@ -43,31 +40,32 @@ impl TagTrait for PointObject {
 }
 
 impl MapObjectTrait for PointObject {
-    fn write_to_map(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_to_map<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         _as_bezier: Option<f64>,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
     ) -> OmapResult<()> {
-        f.write_all(
-            format!(
-                "<object type=\"0\" symbol=\"{}\" rotation=\"{}\">",
-                self.symbol.id(),
-                self.rotation + grivation
-            )
-            .as_bytes(),
+        f.start(
+            "object",
+            &[
+                ("type", &0),
+                ("symbol", &self.symbol.id()),
+                ("rotation", &(self.rotation + grivation)),
+            ],
         )?;
         self.write_tags(f)?;
         self.write_coords(f, None, scale, grivation, inv_combined_scale_factor)?;
-        f.write_all(b"</object>\n")?;
+        f.end("object")?;
+        f.newline()?;
         Ok(())
     }
 
-    fn write_coords(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_coords<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         _as_bezier: Option<f64>,
         scale: Scale,
         grivation: f64,
@@ -77,23 +75,14 @@ impl MapObjectTrait for PointObject {
             self.point
                 .serialize_polyline(scale, grivation, inv_combined_scale_factor)?;
 
-        f.write_all(b"<coords count=\"1\">")?;
-        f.write_all(&bytes)?;
-        f.write_all(b"</coords>")?;
+        f.start("coords", &[("count", &1)])?;
+        f.raw(&bytes)?;
+        f.end("coords")?;
 
         Ok(())
     }
 
-    fn write_tags(&self, f: &mut BufWriter<File>) -> OmapResult<()> {
-        if self.tags.is_empty() {
-            return Ok(());
-        }
-
-        f.write_all(b"<tags>")?;
-        for (key, val) in self.tags.iter() {
-            f.write_all(format!("<t k=\"{key}\">{val}</t>").as_bytes())?;
-        }
-        f.write_all(b"</tags>")?;
-        Ok(())
+    fn tags(&self) -> &HashMap<String, String> {
+        &self.tags
     }
 }
@@ -2,14 +2,11 @@ use crate::{
     objects::{MapObjectTrait, TagTrait},
     serialize::SerializePolyLine,
     symbols::{SymbolTrait, TextSymbol},
+    xml_writer::XmlWriter,
     OmapResult, Scale,
 };
 use geo_types::Point;
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufWriter, Write},
-};
+use std::{collections::HashMap, fmt::Display, io::Write};
 
 /// A TextObject representing anything that has a TextSymbol
 #[derive(Debug, Clone)]
@@ -20,17 +17,21 @@ pub struct TextObject {
     pub symbol: TextSymbol,
     /// the actual text to display,
     pub text: String,
+    /// the rotation in radians counter clockwise from the x-axis of the projection as for point objects,
+    /// None for text upright on the map
+    pub rotation: Option<f64>,
     /// tags for this object
     pub tags: HashMap<String, String>,
 }
 
 impl TextObject {
     /// create a text object from a geo_types::Point and String
-    pub fn from_point(point: Point, symbol: TextSymbol, text: String) -> Self {
+    pub fn from_point(point: Point, symbol: impl Into<TextSymbol>, text: String) -> Self {
         Self {
             point,
-            symbol,
+            symbol: symbol.into(),
             text,
+            rotation: None,
             tags: HashMap::new(),
         }
     }
@@ -43,32 +44,34 @@ impl TagTrait for TextObject {
 }
 
 impl MapObjectTrait for TextObject {
-    fn write_to_map(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_to_map<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         _as_bezier: Option<f64>,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
     ) -> OmapResult<()> {
-        f.write_all(
-            format!(
-                "<object type=\"4\" symbol=\"{}\" h_align=\"1\" v_align=\"2\">",
-                self.symbol.id(),
-            )
-            .as_bytes(),
-        )?;
+        let symbol = self.symbol.id();
+        let rotation = self.rotation.map(|r| r + grivation);
+        let mut attributes: Vec<(&str, &dyn Display)> = vec![("type", &4), ("symbol", &symbol)];
+        if let Some(rotation) = &rotation {
+            attributes.push(("rotation", rotation));
+        }
+        attributes.push(("h_align", &1));
+        attributes.push(("v_align", &2));
+        f.start("object", &attributes)?;
         self.write_tags(f)?;
-        let text = self.text.clone();
         self.write_coords(f, None, scale, grivation, inv_combined_scale_factor)?;
-        f.write_all(format!("<text>{}</text>", text).as_bytes())?;
-        f.write_all(b"</object>\n")?;
+        f.element("text", &[], &self.text)?;
+        f.end("object")?;
+        f.newline()?;
         Ok(())
     }
 
-    fn write_coords(
-        self,
-        f: &mut BufWriter<File>,
+    fn write_coords<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
         _as_bezier: Option<f64>,
         scale: Scale,
         grivation: f64,
@@ -78,22 +81,13 @@ impl MapObjectTrait for TextObject {
             self.point
                 .serialize_polyline(scale, grivation, inv_combined_scale_factor)?;
 
-        f.write_all(b"<coords count=\"1\">")?;
-        f.write_all(&bytes)?;
-        f.write_all(b"</coords>")?;
+        f.start("coords", &[("count", &1)])?;
+        f.raw(&bytes)?;
+        f.end("coords")?;
         Ok(())
     }
 
-    fn write_tags(&self, f: &mut BufWriter<File>) -> OmapResult<()> {
-        if self.tags.is_empty() {
-            return Ok(());
-        }
-
-        f.write_all(b"<tags>")?;
-        for (key, val) in self.tags.iter() {
-            f.write_all(format!("<t k=\"{key}\">{val}</t>").as_bytes())?;
-        }
-        f.write_all(b"</tags>")?;
-        Ok(())
+    fn tags(&self) -> &HashMap<String, String> {
+        &self.tags
     }
 }
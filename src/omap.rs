@@ -1,15 +1,34 @@
 use crate::{
-    objects::{MapObject, PointObject},
-    symbols::{LineSymbol, PointSymbol, Symbol},
-    OmapResult, Scale,
+    contour_assignment::{assign_contour_symbols, ContourAssignmentReport},
+    contour_labels::add_contour_labels,
+    coordinate_mode::ToRelative,
+    deserialize::{attribute, read_georeferencing, read_parts},
+    dot_knolls::make_dotknolls_and_depressions,
+    geometry::{line_string_signed_area, measured_object},
+    map_part::MapPart,
+    min_size::{collapse_to_points, enforce_min_sizes, CollapseRule, MinSizePolicy, MinSizeReport},
+    objects::MapObject,
+    omap_builder::OmapBuilder,
+    slope_lines::add_depression_slope_lines,
+    symbols::{CustomSymbolSet, LineSymbol, Symbol, SymbolSet, SymbolTrait},
+    update::update_file,
+    xml_writer::XmlWriter,
+    CoordinateMode, Crs, OmapError, OmapResult, ReplaceFilter, Scale,
 };
-use geo_types::{Coord, LineString, Point};
+use geo_types::Coord;
 use std::{
     collections::HashMap,
     io::{BufWriter, Write},
+    sync::Arc,
 };
 use std::{ffi::OsStr, fs::File, path::PathBuf};
 
+use quick_xml::{events::Event, Reader};
+
+#[cfg(feature = "geo_ref")]
+use crate::reprojection::Reprojection;
+#[cfg(feature = "geo_ref")]
+use crate::{distortion::distortion_report, DistortionReport, GeomagneticModel};
 #[cfg(feature = "geo_ref")]
 use chrono::Datelike;
 #[cfg(feature = "geo_ref")]
@@ -22,9 +41,83 @@ use world_magnetic_model::{
     GeomagneticField,
 };
 
+/// The order the objects are written to the map file in  
+///
+/// Objects with the same symbol are always written in the order they were added to the map
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ObjectOrder {
+    /// Order the symbols by the drawing priority of their top-most color, so symbols drawn on top are written last.
+    /// Symbols with the same priority are ordered by their id in the symbol set
+    #[default]
+    DrawingPriority,
+    /// Order the symbols by their id in the symbol set
+    SymbolId,
+    /// Write the symbols in the given order, any other symbols are written after them ordered by drawing priority
+    Symbols(Vec<Symbol>),
+}
+
+impl ObjectOrder {
+    /// All objects in the order they are written with the symbol set
+    pub(crate) fn apply<'a>(
+        &self,
+        objects: &'a HashMap<Symbol, Vec<MapObject>>,
+        symbol_set: &SymbolSet,
+    ) -> Vec<&'a MapObject> {
+        // sort first so symbols with equal keys below still get a fixed order
+        let mut symbols: Vec<Symbol> = objects.keys().copied().collect();
+        symbols.sort();
+        match self {
+            ObjectOrder::DrawingPriority => symbols
+                .sort_by_key(|s| (std::cmp::Reverse(symbol_set.drawing_priority(*s)), s.id())),
+            ObjectOrder::SymbolId => symbols.sort_by_key(|s| s.id()),
+            ObjectOrder::Symbols(order) => symbols.sort_by_key(|s| {
+                (
+                    order.iter().position(|o| o == s).unwrap_or(order.len()),
+                    std::cmp::Reverse(symbol_set.drawing_priority(*s)),
+                    s.id(),
+                )
+            }),
+        }
+        symbols.iter().flat_map(|s| objects[s].iter()).collect()
+    }
+}
+
+// declination (if computed), convergence, grid scale factor, elevation scale factor, geographic ref point
+// and the source of the declination (if computed)
+#[cfg(feature = "geo_ref")]
+type GeoRefParameters = (
+    Option<f64>,
+    f64,
+    f64,
+    f64,
+    Option<Coord>,
+    Option<DeclinationSource>,
+);
+
+// the magnetic model, date and height a declination was computed for, so it can be computed at another ref point
+#[cfg(feature = "geo_ref")]
+#[derive(Debug, Clone)]
+struct DeclinationSource {
+    model: Option<GeomagneticModel>,
+    date: chrono::NaiveDate,
+    meters_above_ellipsoid: Option<f64>,
+}
+
+#[cfg(feature = "geo_ref")]
+impl DeclinationSource {
+    fn declination(&self, geo_ref_point: Coord) -> OmapResult<f64> {
+        Omap::get_declination(
+            geo_ref_point,
+            self.meters_above_ellipsoid,
+            Some(self.date),
+            self.model.as_ref(),
+        )
+    }
+}
+
 /// Struct representing an Orienteering map  
 ///
-/// The map will be georeferenced if epsg.is_some() or else it is written in Local space
+/// The map will be georeferenced if crs.is_some() or else it is written in Local space
 #[derive(Debug, Clone)]
 pub struct Omap {
     #[allow(unused)]
@@ -34,42 +127,149 @@ pub struct Omap {
     declination: f64,
     grivation: f64,
     scale: Scale,
-    epsg_crs: Option<u16>,
+    symbol_set: SymbolSet,
+    // the colors and symbols of the file a built-in set was recognized in, written instead of the embedded ones
+    file_symbols: Option<Arc<CustomSymbolSet>>,
+    object_order: ObjectOrder,
+    coordinate_mode: CoordinateMode,
+    crs: Option<Crs>,
     ref_point: Coord,
     geo_ref_point: Option<Coord>,
+    #[cfg(feature = "geo_ref")]
+    declination_source: Option<DeclinationSource>,
+    current_part: usize,
 
-    /// the objects of the map
-    pub objects: HashMap<Symbol, Vec<MapObject>>,
+    /// the parts of the map, each with its own objects.  
+    /// Replaces the `objects` field of earlier versions, which is available for the current part with [Self::objects] and [Self::objects_mut]
+    pub parts: Vec<MapPart>,
 }
 
 impl Omap {
     /// Create a new map in the given scale centered at the `ref_point` (projected coordinates) with an optional CRS and optional `meters_above_sea_level` in elevation  
     ///
-    /// __All coordinates of objects added to the map must be relative the `ref_point`__
+    /// __All coordinates of objects added to the map must be relative the `ref_point`__, unless another [CoordinateMode] is set with [Self::set_coordinate_mode]
     /// The `ref_point` can be retrieved with [Self::get_ref_point]
     pub fn new(
         ref_point: Coord,
         scale: Scale,
-        epsg_crs: Option<u16>,
-        #[allow(unused_variables)] meters_above_sea_level: Option<f64>,
+        crs: Option<Crs>,
+        meters_above_sea_level: Option<f64>,
     ) -> OmapResult<Self> {
-        let (declination, convergence, grid_scale_factor, elevation_scale_factor, geo_ref_point) = {
-            #[cfg(feature = "geo_ref")]
-            if let Some(epsg) = epsg_crs {
-                Self::get_geo_ref_parameters(epsg, ref_point, meters_above_sea_level)?
-            } else {
-                (0., 0., 1., 1., None)
+        let mut builder = OmapBuilder::new(ref_point, scale);
+        builder.crs = crs;
+        builder.meters_above_sea_level = meters_above_sea_level;
+        builder.build()
+    }
+
+    /// Start building a map in the given scale centered at the `ref_point` (projected coordinates) with [OmapBuilder]  
+    /// Use the builder to fix the date of the magnetic declination or to override the georeferencing parameters
+    pub fn builder(ref_point: Coord, scale: Scale) -> OmapBuilder {
+        OmapBuilder::new(ref_point, scale)
+    }
+
+    /// Create a new map in the given scale from objects in absolute projected coordinates with an optional CRS  
+    ///
+    /// The ref point is the centre of the objects' extent rounded to whole 100 meters, so the map coordinates stay small.
+    /// If `elevation_from_contours` is true the elevation of the map is the mean of the Elevation tags of the contours.  
+    /// The map is in [CoordinateMode::Absolute], so objects added later must be in absolute coordinates as well
+    pub fn from_objects(
+        objects: impl IntoIterator<Item = impl Into<MapObject>>,
+        scale: Scale,
+        crs: Option<Crs>,
+        elevation_from_contours: bool,
+    ) -> OmapResult<Self> {
+        // the grid the ref point is rounded to in meters
+        const REF_POINT_GRID: f64 = 100.;
+
+        let objects: Vec<MapObject> = objects.into_iter().map(Into::into).collect();
+
+        let mut min = Coord {
+            x: f64::INFINITY,
+            y: f64::INFINITY,
+        };
+        let mut max = Coord {
+            x: f64::NEG_INFINITY,
+            y: f64::NEG_INFINITY,
+        };
+        for obj in objects.iter() {
+            obj.for_each_coord(|c| {
+                min.x = min.x.min(c.x);
+                min.y = min.y.min(c.y);
+                max.x = max.x.max(c.x);
+                max.y = max.y.max(c.y);
+            });
+        }
+        let ref_point = if min.x <= max.x && min.y <= max.y {
+            let centre = (min + max) / 2.;
+            Coord {
+                x: (centre.x / REF_POINT_GRID).round() * REF_POINT_GRID,
+                y: (centre.y / REF_POINT_GRID).round() * REF_POINT_GRID,
+            }
+        } else {
+            Coord::zero()
+        };
+
+        let mut builder = OmapBuilder::new(ref_point, scale);
+        builder.crs = crs;
+        if elevation_from_contours {
+            let elevations: Vec<f64> = objects
+                .iter()
+                .filter(|o| matches!(o.symbol(), Symbol::Line(l) if l.is_contour()))
+                .filter_map(MapObject::elevation)
+                .collect();
+            if !elevations.is_empty() {
+                builder.meters_above_sea_level =
+                    Some(elevations.iter().sum::<f64>() / elevations.len() as f64);
             }
-            #[cfg(not(feature = "geo_ref"))]
+        }
+
+        let mut omap = builder.build()?;
+        omap.coordinate_mode = CoordinateMode::Absolute;
+        let part = omap.get_current_part_mut();
+        for obj in objects {
+            part.add_object(obj);
+        }
+        Ok(omap)
+    }
+
+    pub(crate) fn from_builder(builder: OmapBuilder) -> OmapResult<Self> {
+        #[cfg(feature = "geo_ref")]
+        let (
+            declination,
+            convergence,
+            grid_scale_factor,
+            elevation_scale_factor,
+            geo_ref_point,
+            declination_source,
+        ) = match &builder.crs {
+            // a CRS that can not be used for computations, so the given parameters are used as is
+            Some(crs)
+                if !crs.supports_computations()
+                    && (builder.declination.is_some() || builder.grivation.is_some()) =>
             {
-                if epsg_crs.is_some() {
-                    return Err(crate::OmapError::DisabledGeoReferencingFeature);
-                }
-                (0., 0., 1., 1., None)
+                (None, 0., 1., 1., None, None)
+            }
+            Some(crs) => Self::get_geo_ref_parameters(crs, &builder)?,
+            None => (None, 0., 1., 1., None, None),
+        };
+        #[cfg(not(feature = "geo_ref"))]
+        let (declination, convergence, grid_scale_factor, elevation_scale_factor, geo_ref_point) = {
+            if builder.crs.is_some() {
+                return Err(OmapError::DisabledGeoReferencingFeature);
             }
+            (None, 0., 1., 1., None)
         };
 
-        let grivation = declination - convergence;
+        let (declination, grivation) = match (builder.declination, builder.grivation) {
+            (Some(declination), Some(grivation)) => (declination, grivation),
+            (Some(declination), None) => (declination, declination - convergence),
+            (None, Some(grivation)) => (grivation + convergence, grivation),
+            (None, None) => {
+                let declination = declination.unwrap_or(0.);
+                (declination, declination - convergence)
+            }
+        };
+        let grid_scale_factor = builder.grid_scale_factor.unwrap_or(grid_scale_factor);
         let combined_scale_factor = grid_scale_factor * elevation_scale_factor;
 
         Ok(Omap {
@@ -77,39 +277,309 @@ impl Omap {
             combined_scale_factor,
             declination,
             grivation,
-            scale,
-            epsg_crs,
-            ref_point,
+            scale: builder.scale,
+            symbol_set: builder.scale.default_symbol_set(),
+            file_symbols: None,
+            object_order: ObjectOrder::default(),
+            coordinate_mode: CoordinateMode::default(),
+            crs: builder.crs,
+            ref_point: builder.ref_point,
             geo_ref_point,
-            objects: HashMap::new(),
+            #[cfg(feature = "geo_ref")]
+            declination_source,
+            current_part: 0,
+            parts: vec![MapPart::new("map")],
         })
     }
 
-    /// Reserve capacity for `cap` elements for key `symbol` in the objects hashmap
-    pub fn reserve_capacity(&mut self, symbol: impl Into<Symbol>, cap: usize) {
-        let symbol = symbol.into();
-        if let Some(obj) = self.objects.get_mut(&symbol) {
-            obj.reserve(cap);
-        } else {
-            let _ = self.objects.insert(symbol, Vec::with_capacity(cap));
+    /// Read a map from an omap file  
+    ///
+    /// The georeferencing of the file is kept as is and the coordinates of the objects are relative the file's ref point.  
+    /// The built-in symbol sets are recognized by their name and the ids, types and codes of their symbols, so colors and symbols edited in Mapper are kept when the map is written again.
+    /// Any other symbol set is loaded as a [SymbolSet::Custom].  
+    /// Bezier curves are flattened to line strings and objects with unknown symbols are skipped
+    pub fn read_from_file(path: PathBuf) -> OmapResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let mut reader = Reader::from_str(&content);
+
+        let mut georef = None;
+        let mut symbol_set = None;
+        let mut file_symbols = None;
+        let mut parts = Vec::new();
+        let mut current_part = 0;
+
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => match e.name().as_ref() {
+                    b"georeferencing" => georef = Some(read_georeferencing(&mut reader, &e)?),
+                    b"symbols" => {
+                        let _ = reader.read_to_end_into(e.name(), &mut Vec::new())?;
+                    }
+                    b"parts" => {
+                        let Some(georef) = &georef else {
+                            return Err(OmapError::InvalidFile(
+                                "the map objects come before the georeferencing".to_string(),
+                            ));
+                        };
+                        let symbol_set = match &symbol_set {
+                            Some(symbol_set) => symbol_set,
+                            None => {
+                                let custom = CustomSymbolSet::from_xml(&content)?;
+                                let read = match SymbolSet::built_in_like(&custom) {
+                                    Some(built_in) => {
+                                        file_symbols = Some(Arc::new(custom));
+                                        built_in
+                                    }
+                                    None => custom.into(),
+                                };
+                                symbol_set.insert(read)
+                            }
+                        };
+                        current_part = attribute(&e, "current")?
+                            .and_then(|c| c.parse().ok())
+                            .unwrap_or(0);
+                        parts.extend(read_parts(&mut reader, georef, symbol_set)?);
+                    }
+                    _ => (),
+                },
+                Event::Eof => break,
+                _ => (),
+            }
+            buf.clear();
+        }
+
+        let Some(georef) = georef else {
+            return Err(OmapError::InvalidFile(
+                "the georeferencing is missing".to_string(),
+            ));
+        };
+
+        if parts.is_empty() {
+            parts.push(MapPart::new("map"));
         }
+
+        Ok(Omap {
+            elevation_scale_factor: georef.elevation_scale_factor,
+            combined_scale_factor: georef.combined_scale_factor,
+            declination: georef.declination,
+            grivation: georef.grivation,
+            scale: georef.scale,
+            symbol_set: symbol_set.unwrap_or_else(|| georef.scale.default_symbol_set()),
+            file_symbols,
+            object_order: ObjectOrder::default(),
+            coordinate_mode: CoordinateMode::default(),
+            crs: georef.crs,
+            ref_point: georef.ref_point,
+            geo_ref_point: georef.geo_ref_point,
+            #[cfg(feature = "geo_ref")]
+            declination_source: None,
+            current_part: current_part.min(parts.len() - 1),
+            parts,
+        })
     }
 
-    /// Insert an object in the objects hashmap  
-    /// __All coordinates of objects added to the map must be relative the map's `ref_point`__
+    /// Reserve capacity for `cap` elements for key `symbol` in the objects hashmap of the current part
+    pub fn reserve_capacity(&mut self, symbol: impl Into<Symbol>, cap: usize) {
+        self.get_current_part_mut().reserve_capacity(symbol, cap);
+    }
+
+    /// Insert an object in the objects hashmap of the current part  
+    /// __All coordinates of objects added to the map must be in the map's [CoordinateMode]__
     pub fn add_object(&mut self, obj: impl Into<MapObject>) {
-        let obj = obj.into();
-        let key = obj.symbol();
-        if let Some(val) = self.objects.get_mut(&key) {
-            val.push(obj);
-        } else {
-            let _ = self.objects.insert(key, vec![obj]);
+        self.get_current_part_mut().add_object(obj);
+    }
+
+    /// Insert an object in the objects hashmap of the part named `part`, the part is added if it does not exist  
+    /// __All coordinates of objects added to the map must be in the map's [CoordinateMode]__
+    pub fn add_object_to_part(&mut self, part: &str, obj: impl Into<MapObject>) {
+        self.add_part(part).add_object(obj);
+    }
+
+    /// Insert an object given in the CRS `crs` in the objects hashmap of the current part. This method is gated behind the `geo_ref`-feature  
+    /// The object is transformed to the map's CRS and [CoordinateMode], and the rotation of points and area patterns is corrected for the difference in convergence.
+    /// Geographic coordinates (e.g. EPSG:4326) are given in degrees with x as longitude and y as latitude
+    #[cfg(feature = "geo_ref")]
+    pub fn add_object_in_crs(
+        &mut self,
+        obj: impl Into<MapObject>,
+        crs: impl Into<Crs>,
+    ) -> OmapResult<()> {
+        self.add_objects_in_crs([obj], crs)
+    }
+
+    /// Insert objects given in the CRS `crs` in the objects hashmap of the current part, see [Self::add_object_in_crs]  
+    /// No objects are added if any of them fails to be transformed
+    #[cfg(feature = "geo_ref")]
+    pub fn add_objects_in_crs(
+        &mut self,
+        objs: impl IntoIterator<Item = impl Into<MapObject>>,
+        crs: impl Into<Crs>,
+    ) -> OmapResult<()> {
+        let Some(map_crs) = &self.crs else {
+            return Err(OmapError::MissingCrs);
+        };
+        let reprojection =
+            Reprojection::new(&crs.into(), map_crs, self.coordinate_mode, self.ref_point)?;
+
+        let objs = objs
+            .into_iter()
+            .map(|obj| {
+                let mut obj = obj.into();
+                reprojection.apply(&mut obj)?;
+                Ok(obj)
+            })
+            .collect::<OmapResult<Vec<_>>>()?;
+        let part = self.get_current_part_mut();
+        for obj in objs {
+            part.add_object(obj);
+        }
+        Ok(())
+    }
+
+    /// Reproject the map to the CRS `crs` with a new `ref_point` (projected coordinates in the new CRS). This method is gated behind the `geo_ref`-feature
+    ///
+    /// The objects of all parts are transformed and stay in the coordinate mode of the map, rotations of point objects and area patterns are corrected for the change of grid north.
+    /// The convergence and grid scale factor are recomputed at the new ref point, the elevation scale factor is kept.
+    /// A declination the map computed is computed again at the new ref point with the same magnetic model and date,
+    /// a declination that was given or read from a file is kept.
+    /// The map is unchanged if any object fails to be transformed
+    #[cfg(feature = "geo_ref")]
+    pub fn reproject(&mut self, crs: impl Into<Crs>, ref_point: Coord) -> OmapResult<()> {
+        let Some(map_crs) = &self.crs else {
+            return Err(OmapError::MissingCrs);
+        };
+        let crs = crs.into();
+        let to_relative = ToRelative::new(self.coordinate_mode, self.ref_point, Some(map_crs))?;
+        let reprojection = Reprojection::new(map_crs, &crs, self.coordinate_mode, ref_point)?;
+
+        let mut parts = self.parts.clone();
+        for obj in parts
+            .iter_mut()
+            .flat_map(|p| p.objects.values_mut().flatten())
+        {
+            to_relative.apply(obj)?;
+            obj.translate(self.ref_point);
+            reprojection.apply(obj)?;
+        }
+
+        let builder = OmapBuilder::new(ref_point, self.scale).crs(crs.clone());
+        let (_, convergence, grid_scale_factor, _, geo_ref_point, _) =
+            Self::get_geo_ref_parameters(&crs, &builder.declination(0.))?;
+        // a computed declination is computed again with the same model and date, a given one is kept
+        if let (Some(source), Some(geo_ref_point)) = (&self.declination_source, geo_ref_point) {
+            self.declination = source.declination(geo_ref_point)?;
         }
+        self.grivation = self.declination - convergence;
+        self.combined_scale_factor = grid_scale_factor * self.elevation_scale_factor;
+        self.crs = Some(crs);
+        self.ref_point = ref_point;
+        self.geo_ref_point = geo_ref_point;
+        self.parts = parts;
+        Ok(())
+    }
+
+    /// Add a new empty part to the map, or get the part with the same name if it already exists
+    pub fn add_part(&mut self, name: &str) -> &mut MapPart {
+        let index = match self.parts.iter().position(|p| p.name == name) {
+            Some(index) => index,
+            None => {
+                self.parts.push(MapPart::new(name));
+                self.parts.len() - 1
+            }
+        };
+        &mut self.parts[index]
+    }
+
+    /// Get the part with the given name
+    pub fn get_part(&self, name: &str) -> Option<&MapPart> {
+        self.parts.iter().find(|p| p.name == name)
+    }
+
+    /// Get the part with the given name mutably
+    pub fn get_part_mut(&mut self, name: &str) -> Option<&mut MapPart> {
+        self.parts.iter_mut().find(|p| p.name == name)
     }
 
-    /// Get the CRS of the map represented by an EPSG code
-    pub fn get_crs(&self) -> Option<u16> {
-        self.epsg_crs
+    /// Get the current part of the map, which [Self::add_object] adds objects to and which is selected when the map is opened in Mapper
+    pub fn get_current_part(&self) -> Option<&MapPart> {
+        self.parts
+            .get(self.current_part)
+            .or_else(|| self.parts.last())
+    }
+
+    /// Get the current part of the map mutably, a part named "map" is added if the map has no parts
+    pub fn get_current_part_mut(&mut self) -> &mut MapPart {
+        if self.parts.is_empty() {
+            self.parts.push(MapPart::new("map"));
+        }
+        let index = self.current_part.min(self.parts.len() - 1);
+        &mut self.parts[index]
+    }
+
+    /// Get the objects of the current part, which is the map's only part unless parts are added  
+    /// Replaces the `objects` field of maps before map parts were added, use [Self::parts] for the objects of all parts
+    pub fn objects(&self) -> &HashMap<Symbol, Vec<MapObject>> {
+        &self.parts[self.current_part].objects
+    }
+
+    /// Get the objects of the current part mutably, see [Self::objects]
+    pub fn objects_mut(&mut self) -> &mut HashMap<Symbol, Vec<MapObject>> {
+        &mut self.get_current_part_mut().objects
+    }
+
+    /// Set the current part of the map by its name, the part is added if it does not exist
+    pub fn set_current_part(&mut self, name: &str) {
+        let _ = self.add_part(name);
+        self.current_part = self
+            .parts
+            .iter()
+            .position(|p| p.name == name)
+            .unwrap_or_default();
+    }
+
+    /// Get the scale of the map
+    pub fn get_scale(&self) -> Scale {
+        self.scale
+    }
+
+    /// Get the symbol set written to the map
+    pub fn get_symbol_set(&self) -> &SymbolSet {
+        &self.symbol_set
+    }
+
+    /// Set the symbol set written to the map, by default [Scale::default_symbol_set]  
+    /// All symbols of the map's objects must be part of the symbol set when writing
+    pub fn set_symbol_set(&mut self, symbol_set: SymbolSet) {
+        self.symbol_set = symbol_set;
+        self.file_symbols = None;
+    }
+
+    /// Get the order the objects are written in
+    pub fn get_object_order(&self) -> &ObjectOrder {
+        &self.object_order
+    }
+
+    /// Set the order the objects are written in, by default [ObjectOrder::DrawingPriority]
+    pub fn set_object_order(&mut self, object_order: ObjectOrder) {
+        self.object_order = object_order;
+    }
+
+    /// Get the coordinate mode of the map's objects
+    pub fn get_coordinate_mode(&self) -> CoordinateMode {
+        self.coordinate_mode
+    }
+
+    /// Set the coordinate mode of the map's objects, by default [CoordinateMode::Relative]  
+    /// The mode applies to all objects of the map, including those already added.
+    /// The objects are converted to coordinates relative the `ref_point` when the map is written
+    pub fn set_coordinate_mode(&mut self, coordinate_mode: CoordinateMode) {
+        self.coordinate_mode = coordinate_mode;
+    }
+
+    /// Get the CRS of the map
+    pub fn get_crs(&self) -> Option<&Crs> {
+        self.crs.as_ref()
     }
 
     /// Get the projected ref point of the map
@@ -123,12 +593,86 @@ impl Omap {
         self.geo_ref_point
     }
 
+    /// Convert projected coordinates in the map's CRS to map coordinates  
+    ///
+    /// Map coordinates are in millimetres on paper relative the ref point, rotated by the grivation and scaled by the combined scale factor,
+    /// with y pointing up as in Mapper's coordinate display (Mapper stores y pointing down in the file)
+    pub fn projected_to_map(&self, c: Coord) -> Coord {
+        let c = c - self.ref_point;
+        let (sin, cos) = self.grivation.sin_cos();
+        let conversion = self.mm_per_meter();
+
+        Coord {
+            x: (c.x * cos - c.y * sin) * conversion,
+            y: (c.x * sin + c.y * cos) * conversion,
+        }
+    }
+
+    /// Convert map coordinates in millimetres on paper to projected coordinates in the map's CRS, the inverse of [Self::projected_to_map]
+    pub fn map_to_projected(&self, c: Coord) -> Coord {
+        let (sin, cos) = self.grivation.sin_cos();
+        let conversion = self.mm_per_meter();
+        let (x, y) = (c.x / conversion, c.y / conversion);
+
+        Coord {
+            x: x * cos + y * sin,
+            y: -x * sin + y * cos,
+        } + self.ref_point
+    }
+
+    /// Convert geographic coordinates in degrees (x as longitude and y as latitude) to map coordinates in millimetres on paper, see [Self::projected_to_map]  
+    /// This method is gated behind the `geo_ref`-feature and requires the map to have a CRS
+    #[cfg(feature = "geo_ref")]
+    pub fn geographic_to_map(&self, c: Coord) -> OmapResult<Coord> {
+        let Some(crs) = &self.crs else {
+            return Err(OmapError::MissingCrs);
+        };
+        let mut c = Coord {
+            x: c.x.to_radians(),
+            y: c.y.to_radians(),
+        };
+        transform(&Proj::from_epsg_code(4326)?, &crs.to_proj()?, &mut c)?;
+        Ok(self.projected_to_map(c))
+    }
+
+    /// Convert map coordinates in millimetres on paper to geographic coordinates in degrees (x as longitude and y as latitude), the inverse of [Self::geographic_to_map]  
+    /// This method is gated behind the `geo_ref`-feature and requires the map to have a CRS
+    #[cfg(feature = "geo_ref")]
+    pub fn map_to_geographic(&self, c: Coord) -> OmapResult<Coord> {
+        let Some(crs) = &self.crs else {
+            return Err(OmapError::MissingCrs);
+        };
+        let mut c = self.map_to_projected(c);
+        transform(&crs.to_proj()?, &Proj::from_epsg_code(4326)?, &mut c)?;
+        Ok(Coord {
+            x: c.x.to_degrees(),
+            y: c.y.to_degrees(),
+        })
+    }
+
+    /// Sample the grid scale factor and meridian convergence on a grid of `samples_per_axis` × `samples_per_axis` points over the extent of the map's objects
+    /// and report their largest deviation from the values at the ref point. This method is gated behind the `geo_ref`-feature  
+    /// The map must have a CRS that can be used for computations
+    #[cfg(feature = "geo_ref")]
+    pub fn distortion_report(&self, samples_per_axis: usize) -> OmapResult<DistortionReport> {
+        let Some(crs) = &self.crs else {
+            return Err(OmapError::MissingCrs);
+        };
+        distortion_report(
+            &self.parts,
+            crs,
+            self.coordinate_mode,
+            self.ref_point,
+            samples_per_axis,
+        )
+    }
+
     /// Merge line objects that are tip to tail. This method is gated behind the `merge_lines`-feature     
     /// Line ends (directed) of the same symbol that are less than `delta` units (same units as the crs most often meters) apart are merged.  
     /// Elevation tags are respected and only elements with equal Elevation tags can be merged
     #[cfg(feature = "merge_lines")]
     pub fn merge_lines(&mut self, delta: f64) {
-        for (key, map_objects) in self.objects.iter_mut() {
+        for (key, map_objects) in self.parts.iter_mut().flat_map(|p| p.objects.iter_mut()) {
             if !key.is_line_symbol() {
                 continue;
             }
@@ -285,38 +829,91 @@ impl Omap {
         }
     }
 
-    /// Turn small contour loops to dotknolls and depressions and remove the smallest ones
+    /// Turn small contour loops to dotknolls and depressions and remove the smallest ones  
+    ///
+    /// A loop is a knoll if its Elevation tag is above the one of the smallest contour loop enclosing it and a depression if it is below,
+    /// so the classification does not depend on the orientation of the lines. Loops without tags or an enclosing loop with a different elevation
+    /// are classified by their winding, counter clockwise loops are knolls.  
+    /// Knolls lower than `min_knoll_height` and depressions shallower than `min_depression_depth` above or below the enclosing contour are removed.  
+    /// The areas are in square meters and the aspect is measured in projected coordinates in every [CoordinateMode], ISOM and ISSprOM contours get the points of their own symbol set
     pub fn make_dotknolls_and_depressions(
         &mut self,
         min_area: f64,
         max_area: f64,
         elongated_aspect: f64,
-    ) {
-        let keys = [
-            Symbol::Line(LineSymbol::Contour),
-            Symbol::Line(LineSymbol::FormLine),
-            Symbol::Line(LineSymbol::IndexContour),
-        ];
+        min_knoll_height: f64,
+        min_depression_depth: f64,
+    ) -> OmapResult<()> {
+        let to_relative = self.to_relative()?;
+        make_dotknolls_and_depressions(
+            &mut self.parts,
+            &to_relative,
+            min_area,
+            max_area,
+            elongated_aspect,
+            min_knoll_height,
+            min_depression_depth,
+        )
+    }
+
+    /// Add slope lines on the inside of closed depression contours at their straightest spots,
+    /// one slope line per `spacing` meters of contour and at least one on every depression contour.  
+    /// Depressions are found from the Elevation tags as in [Self::make_dotknolls_and_depressions], or else by their clockwise winding.  
+    /// Returns the number of added slope lines
+    pub fn add_depression_slope_lines(&mut self, spacing: f64) -> OmapResult<usize> {
+        let to_relative = self.to_relative()?;
+        add_depression_slope_lines(&mut self.parts, &to_relative, spacing)
+    }
+
+    /// Assign the contour symbols from the Elevation tags of the contours, e.g. with an `equidistance` of 5 m and an `index_interval` of 25 m  
+    ///
+    /// Lines on the index interval become index contours, lines on the equidistance contours and lines halfway between them form lines.
+    /// Lines at any other elevation become basemap contours and are reported, lines without an Elevation tag keep their symbol.  
+    /// ISOM and ISSprOM contours are assigned within their own symbol set
+    pub fn assign_contour_symbols(
+        &mut self,
+        equidistance: f64,
+        index_interval: f64,
+    ) -> ContourAssignmentReport {
+        assign_contour_symbols(&mut self.parts, equidistance, index_interval)
+    }
 
-        for key in keys {
-            let contours = self.objects.get_mut(&key);
+    /// Label the index contours with the values of their Elevation tags at straight spots clear of other objects,
+    /// one label per `spacing` millimetres of contour on the map and at least one on every contour with room for it.  
+    /// The labels are rotated along the contour with their top on the higher side, found from the neighbouring contours
+    /// or else as in [Self::add_depression_slope_lines] for closed contours, and the contour is cut beneath the labels.  
+    /// Returns the number of added labels
+    pub fn add_contour_labels(&mut self, spacing: f64) -> OmapResult<usize> {
+        let (to_relative, mm_per_meter) = (self.to_relative()?, self.mm_per_meter());
+        add_contour_labels(
+            &mut self.parts,
+            &to_relative,
+            &self.symbol_set,
+            mm_per_meter,
+            spacing,
+        )
+    }
 
-            if contours.is_none() {
+    /// Mark closed basemap contour loops wound clockwise as depressions
+    pub fn mark_basemap_depressions(&mut self) {
+        for part in self.parts.iter_mut() {
+            let basemap = part
+                .objects
+                .get_mut(&Symbol::Line(LineSymbol::BasemapContour));
+            if basemap.is_none() {
                 continue;
             }
 
-            let contours = contours.unwrap();
-            let mut small_loops = Vec::with_capacity(contours.len());
+            let basemap = basemap.unwrap();
+
+            let mut neg_basemap = Vec::new();
 
             let mut i = 0;
-            while i < contours.len() {
-                let contour_object = &contours[i];
-                if let MapObject::LineObject(o) = contour_object {
+            while i < basemap.len() {
+                if let MapObject::LineObject(o) = &basemap[i] {
                     if o.line.is_closed() {
-                        let area = line_string_signed_area(&o.line);
-
-                        if area.abs() <= max_area {
-                            small_loops.push(contours.swap_remove(i));
+                        if line_string_signed_area(&o.line) < 0. {
+                            neg_basemap.push(basemap.swap_remove(i));
                         } else {
                             i += 1;
                         }
@@ -324,81 +921,35 @@ impl Omap {
                         i += 1;
                     }
                 } else {
-                    panic!("Non-line object under contour symbol in objects hashmap");
+                    panic!("Non LineObject under Basemap symbol in objects hashmap");
                 }
             }
 
-            for small_loop in small_loops {
-                if let MapObject::LineObject(o) = &small_loop {
-                    let area = line_string_signed_area(&o.line);
-
-                    // ignore too small loops
-                    if area.abs() < min_area {
-                        continue;
-                    }
-
-                    let (aspect, mid_point, rotation) =
-                        line_string_aspect_midpoint_rotation(&o.line);
-
-                    if area < 0. {
-                        let u_depression =
-                            PointObject::from_point(Point(mid_point), PointSymbol::UDepression, 0.);
-                        self.add_object(MapObject::PointObject(u_depression));
-                    } else if aspect < elongated_aspect {
-                        let dot_knoll =
-                            PointObject::from_point(Point(mid_point), PointSymbol::DotKnoll, 0.);
-                        self.add_object(MapObject::PointObject(dot_knoll));
-                    } else {
-                        let long_dot_knoll = PointObject::from_point(
-                            Point(mid_point),
-                            PointSymbol::ElongatedDotKnoll,
-                            rotation,
-                        );
-                        self.add_object(MapObject::PointObject(long_dot_knoll));
-                    }
-                }
-            }
+            let _ = part
+                .objects
+                .insert(Symbol::Line(LineSymbol::NegBasemapContour), neg_basemap);
         }
     }
 
-    /// Mark closed basemap contour loops wound clockwise as depressions
-    pub fn mark_basemap_depressions(&mut self) {
-        let basemap = self
-            .objects
-            .get_mut(&Symbol::Line(LineSymbol::BasemapContour));
-        if basemap.is_none() {
-            return;
-        }
-
-        let basemap = basemap.unwrap();
-
-        let mut neg_basemap = Vec::new();
-
-        let mut i = 0;
-        while i < basemap.len() {
-            if let MapObject::LineObject(o) = &basemap[i] {
-                if o.line.is_closed() {
-                    if line_string_signed_area(&o.line) < 0. {
-                        neg_basemap.push(basemap.swap_remove(i));
-                    } else {
-                        i += 1;
-                    }
-                } else {
-                    i += 1;
-                }
-            } else {
-                panic!("Non LineObject under Basemap symbol in objects hashmap");
-            }
-        }
+    /// Generalize areas smaller than the minimum area and lines shorter than the minimum length of their symbol at the scale of the map
+    /// The objects are dropped, exaggerated or converted to a point symbol by the `policy`, symbols without a minimum size are left as they are.
+    /// Returns a report of the changed objects
+    pub fn enforce_min_sizes(&mut self, policy: MinSizePolicy) -> OmapResult<MinSizeReport> {
+        let to_relative = self.to_relative()?;
+        enforce_min_sizes(&mut self.parts, &to_relative, self.scale, policy)
+    }
 
-        let _ = self
-            .objects
-            .insert(Symbol::Line(LineSymbol::NegBasemapContour), neg_basemap);
+    /// Replace line and area objects smaller than the max size of a matching rule by point objects, see [CollapseRule]
+    /// The points are placed at the centroid of the objects and rotated along their major axis, points from lines keep the direction of the line.
+    /// Returns the number of collapsed objects
+    pub fn collapse_to_points(&mut self, rules: &[CollapseRule]) -> OmapResult<usize> {
+        let to_relative = self.to_relative()?;
+        collapse_to_points(&mut self.parts, &to_relative, rules)
     }
 
     /// Write the map to an omap file,  
     /// if `path` is an invalid path then "auto_generated_map.omap" is the new path
-    pub fn write_to_file(self, mut path: PathBuf, bezier_error: Option<f64>) -> OmapResult<()> {
+    pub fn write_to_file(&self, mut path: PathBuf, bezier_error: Option<f64>) -> OmapResult<()> {
         if path.as_os_str().is_empty() || path.is_dir() {
             path.push("auto_generated_map.omap");
         }
@@ -413,75 +964,219 @@ impl Omap {
             let _ = std::fs::create_dir_all(dir_path);
         }
 
+        // check before creating the file so an existing file is not truncated
+        self.check_symbol_set(&self.symbol_set)?;
+        let to_relative = self.to_relative()?;
+
         let f = File::create(&path)?;
-        let mut f = BufWriter::new(f);
+        self.write_map(BufWriter::new(f), bezier_error, &to_relative)
+    }
 
-        self.write_header(&mut f)?;
-        self.write_colors_symbols(&mut f)?;
-        self.write_objects(&mut f, bezier_error)?;
-        Self::write_end_of_file(&mut f)?;
-        Ok(())
+    /// Write the map in the omap format to any writer, e.g. a `Vec<u8>`, a socket or stdout  
+    /// Many small writes are made, so the writer should be buffered
+    pub fn write_to<W: Write>(&self, writer: W, bezier_error: Option<f64>) -> OmapResult<()> {
+        self.check_symbol_set(&self.symbol_set)?;
+        let to_relative = self.to_relative()?;
+
+        self.write_map(writer, bezier_error, &to_relative)
+    }
+
+    /// Update an existing omap file with the objects of the map  
+    /// Only the objects in the file matched by `filter` are replaced, every other object, template, view and print setting is left untouched.
+    /// The objects of each part are added to the part of the file with the same name, parts missing in the file are added to it.
+    /// The new objects are written with the file's georeferencing,
+    /// so the map must have the same CRS as the file
+    pub fn update_file(
+        &self,
+        path: PathBuf,
+        filter: &ReplaceFilter,
+        bezier_error: Option<f64>,
+    ) -> OmapResult<()> {
+        update_file(
+            &path,
+            filter,
+            &self.parts,
+            &self.object_order,
+            &self.to_relative()?,
+            self.crs.as_ref(),
+            bezier_error,
+        )
     }
 }
 
 // private functions
 impl Omap {
-    fn write_header(&self, f: &mut BufWriter<File>) -> OmapResult<()> {
-        f.write_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<map xmlns=\"http://openorienteering.org/apps/mapper/xml/v2\" version=\"9\">\n<notes></notes>\n")?;
+    // converts the coordinates of the objects to projected coordinates relative the ref point
+    fn to_relative(&self) -> OmapResult<ToRelative> {
+        ToRelative::new(self.coordinate_mode, self.ref_point, self.crs.as_ref())
+    }
 
-        let geo_ref_bytes = {
-            if let (Some(epsg), Some(geo_ref_point)) = (self.epsg_crs, self.geo_ref_point) {
-                self.get_georef_bytes(epsg, geo_ref_point)
-            } else {
-                format!(
-                    "<georeferencing scale=\"{}\"><projected_crs id=\"Local\">\
-                <ref_point x=\"{}\" y=\"{}\"/></projected_crs></georeferencing>\n",
-                    self.scale, self.ref_point.x, self.ref_point.y
-                )
-                .into_bytes()
+    // millimetres on paper per meter in the projection
+    fn mm_per_meter(&self) -> f64 {
+        self.scale.map_units_per_meter() / 1000. / self.combined_scale_factor
+    }
+
+    fn check_symbol_set(&self, symbol_set: &SymbolSet) -> OmapResult<()> {
+        for symbol in self.parts.iter().flat_map(|p| p.objects.keys()) {
+            if !symbol_set.contains(*symbol) {
+                return Err(OmapError::SymbolNotInSymbolSet(
+                    *symbol,
+                    symbol_set.name().to_string(),
+                ));
             }
-        };
-        f.write_all(&geo_ref_bytes)?;
+        }
+        Ok(())
+    }
 
+    fn write_map<W: Write>(
+        &self,
+        writer: W,
+        bezier_error: Option<f64>,
+        to_relative: &ToRelative,
+    ) -> OmapResult<()> {
+        let mut writer = XmlWriter::new(writer);
+        self.write_header(&mut writer)?;
+        self.write_colors_symbols(&mut writer)?;
+        self.write_objects(&mut writer, bezier_error, to_relative)?;
+        Self::write_end_of_file(&mut writer)?;
+        writer.flush()?;
         Ok(())
     }
 
-    fn get_georef_bytes(&self, epsg: u16, geo_ref_point: Coord) -> Vec<u8> {
-        format!("<georeferencing scale=\"{}\" grid_scale_factor=\"{}\" auxiliary_scale_factor=\"{}\" declination=\"{}\" grivation=\"{}\">\
-        <projected_crs id=\"EPSG\"><spec language=\"PROJ.4\">+init=epsg:{}</spec><parameter>{}</parameter>\
-        <ref_point x=\"{}\" y=\"{}\"/></projected_crs><geographic_crs id=\"Geographic coordinates\">\
-        <spec language=\"PROJ.4\">+proj=latlong +datum=WGS84</spec>\
-        <ref_point_deg lat=\"{}\" lon=\"{}\"/></geographic_crs></georeferencing>\n",
-        self.scale, self.combined_scale_factor, self.elevation_scale_factor, self.declination.to_degrees(), self.grivation.to_degrees(),
-        epsg, epsg, self.ref_point.x, self.ref_point.y, geo_ref_point.y.to_degrees(), geo_ref_point.x.to_degrees()).into_bytes()
+    fn write_header<W: Write>(&self, f: &mut XmlWriter<W>) -> OmapResult<()> {
+        f.raw(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n")?;
+        f.start(
+            "map",
+            &[
+                ("xmlns", &"http://openorienteering.org/apps/mapper/xml/v2"),
+                ("version", &9),
+            ],
+        )?;
+        f.newline()?;
+        f.element("notes", &[], "")?;
+        f.newline()?;
+
+        if let Some(crs) = &self.crs {
+            self.write_georeferencing(f, crs)?;
+        } else {
+            if self.declination == 0. && self.grivation == 0. && self.combined_scale_factor == 1. {
+                f.start("georeferencing", &[("scale", &self.scale)])?;
+            } else {
+                f.start(
+                    "georeferencing",
+                    &[
+                        ("scale", &self.scale),
+                        ("grid_scale_factor", &self.combined_scale_factor),
+                        ("declination", &self.declination.to_degrees()),
+                        ("grivation", &self.grivation.to_degrees()),
+                    ],
+                )?;
+            }
+            f.start("projected_crs", &[("id", &"Local")])?;
+            f.empty(
+                "ref_point",
+                &[("x", &self.ref_point.x), ("y", &self.ref_point.y)],
+            )?;
+            f.end("projected_crs")?;
+            f.end("georeferencing")?;
+        }
+        f.newline()?;
+
+        Ok(())
     }
 
-    fn write_colors_symbols(&self, f: &mut BufWriter<File>) -> OmapResult<()> {
-        f.write_all(include_str!("colors.txt").as_bytes())?;
-        match self.scale {
-            Scale::S10_000 => {
-                f.write_all(include_str!("symbols_10.txt").as_bytes())?;
+    fn write_georeferencing<W: Write>(&self, f: &mut XmlWriter<W>, crs: &Crs) -> OmapResult<()> {
+        f.start(
+            "georeferencing",
+            &[
+                ("scale", &self.scale),
+                ("grid_scale_factor", &self.combined_scale_factor),
+                ("auxiliary_scale_factor", &self.elevation_scale_factor),
+                ("declination", &self.declination.to_degrees()),
+                ("grivation", &self.grivation.to_degrees()),
+            ],
+        )?;
+        // Mapper passes the spec to PROJ, which accepts both PROJ strings and WKT
+        match crs {
+            Crs::Epsg(code) => {
+                f.start("projected_crs", &[("id", &"EPSG")])?;
+                f.element("spec", &[("language", &"PROJ.4")], &crs.spec())?;
+                f.element("parameter", &[], &code.to_string())?;
             }
-            Scale::S15_000 => {
-                f.write_all(include_str!("symbols_15.txt").as_bytes())?;
+            Crs::Proj(_) | Crs::Wkt(_) => {
+                f.start("projected_crs", &[("id", &"PROJ.4")])?;
+                f.element("spec", &[("language", &"PROJ.4")], &crs.spec())?;
             }
         }
+        f.empty(
+            "ref_point",
+            &[("x", &self.ref_point.x), ("y", &self.ref_point.y)],
+        )?;
+        f.end("projected_crs")?;
+        if let Some(geo_ref_point) = self.geo_ref_point {
+            f.start("geographic_crs", &[("id", &"Geographic coordinates")])?;
+            f.element(
+                "spec",
+                &[("language", &"PROJ.4")],
+                "+proj=latlong +datum=WGS84",
+            )?;
+            f.empty(
+                "ref_point_deg",
+                &[
+                    ("lat", &geo_ref_point.y.to_degrees()),
+                    ("lon", &geo_ref_point.x.to_degrees()),
+                ],
+            )?;
+            f.end("geographic_crs")?;
+        }
+        f.end("georeferencing")?;
         Ok(())
     }
 
-    fn write_objects(self, f: &mut BufWriter<File>, bezier_error: Option<f64>) -> OmapResult<()> {
-        let num_objects = self.objects.values().fold(0, |acc, v| acc + v.len());
-
-        f.write_all(
-            format!(
-                "<parts count=\"1\" current=\"0\">\n<part name=\"map\"><objects count=\"{num_objects}\">\n"
-            )
-            .as_bytes(),
-        )?;
+    fn write_colors_symbols<W: Write>(&self, f: &mut XmlWriter<W>) -> OmapResult<()> {
+        let (colors, symbols) = match &self.file_symbols {
+            Some(file_symbols) => (file_symbols.colors_xml(), file_symbols.symbols_xml()),
+            None => (self.symbol_set.colors(), self.symbol_set.symbols()),
+        };
+        f.raw(colors.as_bytes())?;
+        f.newline()?;
+        f.start("barrier", &[("version", &6), ("required", &"0.6.0")])?;
+        f.newline()?;
+        f.raw(symbols.as_bytes())?;
+        f.newline()?;
+        Ok(())
+    }
 
+    fn write_objects<W: Write>(
+        &self,
+        f: &mut XmlWriter<W>,
+        bezier_error: Option<f64>,
+        to_relative: &ToRelative,
+    ) -> OmapResult<()> {
         let inv_combined_scale_factor = 1. / self.combined_scale_factor;
-        for sym_vals in self.objects.into_values() {
-            for obj in sym_vals {
+
+        // Mapper expects at least one part
+        let default_part = [MapPart::new("map")];
+        let parts = if self.parts.is_empty() {
+            &default_part[..]
+        } else {
+            &self.parts[..]
+        };
+        let current_part = self.current_part.min(parts.len() - 1);
+
+        f.start(
+            "parts",
+            &[("count", &parts.len()), ("current", &current_part)],
+        )?;
+        f.newline()?;
+        for part in parts {
+            let objects = self.object_order.apply(&part.objects, &self.symbol_set);
+
+            f.start("part", &[("name", &part.name)])?;
+            f.start("objects", &[("count", &objects.len())])?;
+            f.newline()?;
+            for obj in objects {
+                let obj = measured_object(to_relative, obj)?;
                 obj.write_to_map(
                     f,
                     bezier_error,
@@ -490,38 +1185,72 @@ impl Omap {
                     inv_combined_scale_factor,
                 )?;
             }
+            f.end("objects")?;
+            f.end("part")?;
+            f.newline()?;
         }
-
-        f.write_all(b"</objects></part>\n</parts>\n")?;
+        f.end("parts")?;
+        f.newline()?;
         Ok(())
     }
 
-    fn write_end_of_file(f: &mut BufWriter<File>) -> OmapResult<()> {
-        f.write_all(b"<templates count=\"0\" first_front_template=\"0\">\n<defaults use_meters_per_pixel=\"true\" meters_per_pixel=\"0\" dpi=\"0\" scale=\"0\"/></templates>\n<view>\n")?;
-        f.write_all(b"<grid color=\"#646464\" display=\"0\" alignment=\"0\" additional_rotation=\"0\" unit=\"1\" h_spacing=\"500\" v_spacing=\"500\" h_offset=\"0\" v_offset=\"0\" snapping_enabled=\"true\"/>\n")?;
-        f.write_all(b"<map_view zoom=\"1\" position_x=\"0\" position_y=\"0\"><map opacity=\"1\" visible=\"true\"/><templates count=\"0\"/></map_view>\n</view>\n</barrier>\n</map>")?;
+    fn write_end_of_file<W: Write>(f: &mut XmlWriter<W>) -> OmapResult<()> {
+        f.raw(b"<templates count=\"0\" first_front_template=\"0\">\n<defaults use_meters_per_pixel=\"true\" meters_per_pixel=\"0\" dpi=\"0\" scale=\"0\"/></templates>\n<view>\n")?;
+        f.raw(b"<grid color=\"#646464\" display=\"0\" alignment=\"0\" additional_rotation=\"0\" unit=\"1\" h_spacing=\"500\" v_spacing=\"500\" h_offset=\"0\" v_offset=\"0\" snapping_enabled=\"true\"/>\n")?;
+        f.raw(b"<map_view zoom=\"1\" position_x=\"0\" position_y=\"0\"><map opacity=\"1\" visible=\"true\"/><templates count=\"0\"/></map_view>\n</view>\n")?;
+        f.end("barrier")?;
+        f.newline()?;
+        f.end("map")?;
         Ok(())
     }
 
     #[cfg(feature = "geo_ref")]
-    fn get_geo_ref_parameters(
-        epsg: u16,
-        ref_point: Coord,
-        meters_above_sea: Option<f64>,
-    ) -> OmapResult<(f64, f64, f64, f64, Option<Coord>)> {
+    fn get_geo_ref_parameters(crs: &Crs, builder: &OmapBuilder) -> OmapResult<GeoRefParameters> {
         // get geographic ref point
-        let mut geo_ref_point = ref_point;
+        let mut geo_ref_point = builder.ref_point;
         let geo_proj = Proj::from_epsg_code(4326)?;
-        let local_proj = Proj::from_epsg_code(epsg)?;
+        let local_proj = crs.to_proj()?;
         transform(&local_proj, &geo_proj, &mut geo_ref_point)?;
 
-        // get magnetic declination
-        let declination = Self::get_declination(geo_ref_point, meters_above_sea)?;
-        let elevation_scale_factor =
-            Self::get_elevation_scale_factor(geo_ref_point, meters_above_sea);
+        // convert the orthometric height to the height above the ellipsoid
+        let meters_above_ellipsoid = match (&builder.geoid, builder.meters_above_sea_level) {
+            (Some(geoid), Some(meters_above_sea_level)) => {
+                let undulation = geoid
+                    .undulation(geo_ref_point.x.to_degrees(), geo_ref_point.y.to_degrees())
+                    .ok_or(OmapError::OutsideGeoidGrid)?;
+                Some(meters_above_sea_level + undulation)
+            }
+            (_, meters_above_sea_level) => meters_above_sea_level,
+        };
 
-        let (convergence, grid_scale_factor) =
-            Self::get_convergence_and_grid_scale_factor(epsg, geo_ref_point)?;
+        // get magnetic declination, unless it is given
+        let declination_source = if builder.declination.is_none() && builder.grivation.is_none() {
+            Some(DeclinationSource {
+                model: builder.geomagnetic_model.clone(),
+                date: builder
+                    .declination_date
+                    .unwrap_or_else(|| chrono::Local::now().date_naive()),
+                meters_above_ellipsoid,
+            })
+        } else {
+            None
+        };
+        let declination = declination_source
+            .as_ref()
+            .map(|source| source.declination(geo_ref_point))
+            .transpose()?;
+        let elevation_scale_factor =
+            Self::get_elevation_scale_factor(geo_ref_point, meters_above_ellipsoid);
+
+        // the convergence and grid scale factor are not needed if the parameters depending on them are given
+        let (convergence, grid_scale_factor) = if builder.declination.is_some()
+            && builder.grivation.is_some()
+            && builder.grid_scale_factor.is_some()
+        {
+            (0., 1.)
+        } else {
+            Self::get_convergence_and_grid_scale_factor(&local_proj, geo_ref_point)?
+        };
 
         Ok((
             declination,
@@ -529,15 +1258,15 @@ impl Omap {
             grid_scale_factor,
             elevation_scale_factor,
             Some(geo_ref_point),
+            declination_source,
         ))
     }
 
     #[cfg(feature = "geo_ref")]
-    fn get_convergence_and_grid_scale_factor(
-        epsg: u16,
+    pub(crate) fn get_convergence_and_grid_scale_factor(
+        local_proj: &Proj,
         geo_ref_point: Coord,
     ) -> OmapResult<(f64, f64)> {
-        let local_proj = Proj::from_epsg_code(epsg)?;
         let baseline_proj = Proj::from_proj_string(
             format!(
                 "+proj=sterea +lat_0={} +lon_0={} +ellps=WGS84 +units=m",
@@ -554,8 +1283,8 @@ impl Omap {
             geo_types::Line::new(Coord { x: -D / 2., y: 0. }, Coord { x: D / 2., y: 0. });
 
         // Project the stereographic baselines to the local grid
-        transform(&baseline_proj, &local_proj, &mut meridian)?;
-        transform(&baseline_proj, &local_proj, &mut parallel)?;
+        transform(&baseline_proj, local_proj, &mut meridian)?;
+        transform(&baseline_proj, local_proj, &mut parallel)?;
 
         // Points on the same meridian
         let meridian_delta = meridian.delta() / D;
@@ -578,9 +1307,9 @@ impl Omap {
     #[cfg(feature = "geo_ref")]
     fn get_elevation_scale_factor(
         geo_ref_point: Coord,
-        meters_above_sea_level: Option<f64>,
+        meters_above_ellipsoid: Option<f64>,
     ) -> f64 {
-        if let Some(meters_above_sea_level) = meters_above_sea_level {
+        if let Some(meters_above_ellipsoid) = meters_above_ellipsoid {
             // this is (ellipsoid_radius / (ellipsoid_radius + m_above_ellipsoid))
             //
             // ellipsoid_radius = R_equator * (1 - f * sin^2(lat))
@@ -591,7 +1320,7 @@ impl Omap {
 
             let ellipsoid_radius = R_EQUATOR * (1. - F * geo_ref_point.y.sin().powi(2));
 
-            ellipsoid_radius / (ellipsoid_radius + meters_above_sea_level)
+            ellipsoid_radius / (ellipsoid_radius + meters_above_ellipsoid)
         } else {
             1.
         }
@@ -600,87 +1329,38 @@ impl Omap {
     #[cfg(feature = "geo_ref")]
     fn get_declination(
         geo_ref_point: Coord,
-        meters_above_sea_level: Option<f64>,
+        meters_above_ellipsoid: Option<f64>,
+        date: Option<chrono::NaiveDate>,
+        model: Option<&GeomagneticModel>,
     ) -> OmapResult<f64> {
-        let date = chrono::Local::now();
-        let year = date.year();
-        let day = date.ordinal() as u16;
+        let date = date.unwrap_or_else(|| chrono::Local::now().date_naive());
+
+        if let Some(model) = model {
+            return model.declination(
+                geo_ref_point.x.to_degrees(),
+                geo_ref_point.y.to_degrees(),
+                meters_above_ellipsoid.unwrap_or(0.),
+                date,
+            );
+        }
+
+        // the built-in models are WMM2020 and WMM2025
+        let outside_epoch = || OmapError::DateOutsideOfModelEpoch(date, 2020., 2030.);
+        let wmm_date = Date::from_ordinal_date(date.year(), date.ordinal() as u16)
+            .map_err(|_| outside_epoch())?;
 
         let field = GeomagneticField::new(
-            Length::new::<meter>(meters_above_sea_level.unwrap_or(0.) as f32),
+            Length::new::<meter>(meters_above_ellipsoid.unwrap_or(0.) as f32),
             Angle::new::<radian>(geo_ref_point.y as f32),
             Angle::new::<radian>(geo_ref_point.x as f32),
-            Date::from_ordinal_date(year, day)
-                .unwrap_or(Date::from_ordinal_date(2025, 180).unwrap()),
-        )?;
+            wmm_date,
+        )
+        .map_err(|e| match e {
+            world_magnetic_model::Error::DateOutsideOfValidityRange => outside_epoch(),
+            e => e.into(),
+        })?;
         let dec = field.declination().get::<radian>();
 
         Ok(dec as f64)
     }
 }
-
-fn line_string_signed_area(line: &LineString) -> f64 {
-    if line.0.len() < 3 {
-        return 0.;
-    }
-    let mut area: f64 = 0.;
-    for i in 0..line.0.len() - 1 {
-        area += line.0[i].x * line.0[i + 1].y - line.0[i].y * line.0[i + 1].x;
-    }
-    0.5 * area
-}
-
-fn line_string_aspect_midpoint_rotation(line: &LineString) -> (f64, Coord, f64) {
-    let mut midpoint = Coord::zero();
-    for c in line.0.iter() {
-        midpoint = midpoint + *c;
-    }
-    midpoint = midpoint / line.0.len() as f64;
-
-    // Calculate second moments
-    let mu20 = line
-        .0
-        .iter()
-        .map(|p| (p.x - midpoint.x).powi(2))
-        .sum::<f64>();
-    let mu02 = line
-        .0
-        .iter()
-        .map(|p| (p.y - midpoint.y).powi(2))
-        .sum::<f64>();
-    let mu11 = line
-        .0
-        .iter()
-        .map(|p| (p.x - midpoint.x) * (p.y - midpoint.y))
-        .sum::<f64>();
-
-    // Calculate elongation using eigenvalues of the covariance matrix
-    let temp = ((mu20 - mu02).powi(2) + 4.0 * mu11.powi(2)).sqrt();
-    let lambda1 = (mu20 + mu02 + temp) / 2.0;
-    let lambda2 = (mu20 + mu02 - temp) / 2.0;
-
-    // Handle potential numerical issues
-    if lambda2.abs() <= 2. * f64::EPSILON
-        || ((mu20 - mu02).abs() <= 2. * f64::EPSILON && mu11.abs() <= 2. * f64::EPSILON)
-    {
-        return (1., midpoint, 0.);
-    }
-
-    let elongation = (lambda1 / lambda2).sqrt();
-
-    // Calculate the angle of the major axis (in radians)
-    // The eigenvector corresponding to the largest eigenvalue gives the direction
-    let mut angle = 0.5 * f64::atan2(2.0 * mu11, mu20 - mu02);
-
-    // Ensure the angle corresponds to the major (not minor) axis
-    if !(mu20 < mu02 || mu11 >= 0.0) {
-        angle += std::f64::consts::FRAC_PI_2;
-    }
-
-    angle %= std::f64::consts::PI;
-    if angle < 0.0 {
-        angle += std::f64::consts::PI;
-    }
-
-    (elongation, midpoint, angle)
-}
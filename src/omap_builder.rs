@@ -0,0 +1,105 @@
+use geo_types::Coord;
+
+use crate::{Crs, Omap, OmapResult, Scale};
+#[cfg(feature = "geo_ref")]
+use crate::{GeoidGrid, GeomagneticModel};
+
+/// Builder for an [Omap] with a fixed date for the magnetic model or manual georeferencing parameters
+///
+/// Maps built with the same parameters and date get the same georeferencing, no matter when they are built
+#[derive(Debug, Clone)]
+pub struct OmapBuilder {
+    pub(crate) ref_point: Coord,
+    pub(crate) scale: Scale,
+    pub(crate) crs: Option<Crs>,
+    pub(crate) meters_above_sea_level: Option<f64>,
+    #[cfg(feature = "geo_ref")]
+    pub(crate) declination_date: Option<chrono::NaiveDate>,
+    #[cfg(feature = "geo_ref")]
+    pub(crate) geoid: Option<GeoidGrid>,
+    #[cfg(feature = "geo_ref")]
+    pub(crate) geomagnetic_model: Option<GeomagneticModel>,
+    pub(crate) declination: Option<f64>,
+    pub(crate) grivation: Option<f64>,
+    pub(crate) grid_scale_factor: Option<f64>,
+}
+
+impl OmapBuilder {
+    /// Start building a map in the given scale centered at the `ref_point` (projected coordinates)
+    pub fn new(ref_point: Coord, scale: Scale) -> Self {
+        OmapBuilder {
+            ref_point,
+            scale,
+            crs: None,
+            meters_above_sea_level: None,
+            #[cfg(feature = "geo_ref")]
+            declination_date: None,
+            #[cfg(feature = "geo_ref")]
+            geoid: None,
+            #[cfg(feature = "geo_ref")]
+            geomagnetic_model: None,
+            declination: None,
+            grivation: None,
+            grid_scale_factor: None,
+        }
+    }
+
+    /// Set the CRS of the map, e.g. by its EPSG code, the map is written in Local space without it
+    pub fn crs(mut self, crs: impl Into<Crs>) -> Self {
+        self.crs = Some(crs.into());
+        self
+    }
+
+    /// Set the elevation of the map used for the elevation scale factor and the magnetic declination
+    pub fn meters_above_sea_level(mut self, meters_above_sea_level: f64) -> Self {
+        self.meters_above_sea_level = Some(meters_above_sea_level);
+        self
+    }
+
+    /// Set the date the magnetic declination is computed for, by default today. This method is gated behind the `geo_ref`-feature
+    #[cfg(feature = "geo_ref")]
+    pub fn declination_date(mut self, date: chrono::NaiveDate) -> Self {
+        self.declination_date = Some(date);
+        self
+    }
+
+    /// Set the geoid used to convert the elevation above sea level to the height above the ellipsoid, without it the geoid is assumed to coincide with the ellipsoid.
+    /// The ref point must be covered by the grid. This method is gated behind the `geo_ref`-feature
+    #[cfg(feature = "geo_ref")]
+    pub fn geoid(mut self, geoid: GeoidGrid) -> Self {
+        self.geoid = Some(geoid);
+        self
+    }
+
+    /// Compute the magnetic declination with a model loaded from a coefficient file instead of the built-in WMM.
+    /// This method is gated behind the `geo_ref`-feature
+    #[cfg(feature = "geo_ref")]
+    pub fn geomagnetic_model(mut self, model: GeomagneticModel) -> Self {
+        self.geomagnetic_model = Some(model);
+        self
+    }
+
+    /// Override the magnetic declination in radians instead of computing it from the magnetic model
+    pub fn declination(mut self, declination: f64) -> Self {
+        self.declination = Some(declination);
+        self
+    }
+
+    /// Override the grivation (the angle between magnetic north and grid north) in radians
+    /// If no declination is given it is derived from the grivation and the meridian convergence
+    pub fn grivation(mut self, grivation: f64) -> Self {
+        self.grivation = Some(grivation);
+        self
+    }
+
+    /// Override the grid scale factor of the projection, it is still combined with the elevation scale factor
+    pub fn grid_scale_factor(mut self, grid_scale_factor: f64) -> Self {
+        self.grid_scale_factor = Some(grid_scale_factor);
+        self
+    }
+
+    /// Build the map
+    pub fn build(self) -> OmapResult<Omap> {
+        Omap::from_builder(self)
+    }
+}
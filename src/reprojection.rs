@@ -0,0 +1,88 @@
+use geo_types::Coord;
+use proj4rs::{transform::transform, Proj};
+
+use crate::{objects::MapObject, CoordinateMode, Crs, OmapResult};
+
+/// Transformation of objects from a source CRS to the [CoordinateMode] of a map
+pub(crate) struct Reprojection {
+    from: Proj,
+    to: Proj,
+    // the geographic CRS if the map is in geographic coordinates
+    geographic: Option<Proj>,
+    offset: Coord,
+}
+
+impl Reprojection {
+    pub(crate) fn new(
+        from: &Crs,
+        to: &Crs,
+        mode: CoordinateMode,
+        ref_point: Coord,
+    ) -> OmapResult<Self> {
+        let (geographic, offset) = match mode {
+            CoordinateMode::Relative => (None, -ref_point),
+            CoordinateMode::Absolute => (None, Coord::zero()),
+            CoordinateMode::Geographic => (Some(Proj::from_epsg_code(4326)?), Coord::zero()),
+        };
+        Ok(Reprojection {
+            from: from.to_proj()?,
+            to: to.to_proj()?,
+            geographic,
+            offset,
+        })
+    }
+
+    /// Transform the geometry of the object and correct its rotation for the difference in convergence between the CRSs
+    pub(crate) fn apply(&self, obj: &mut MapObject) -> OmapResult<()> {
+        match obj {
+            MapObject::PointObject(point_object) => {
+                point_object.rotation += self.rotation_difference(point_object.point.0)?;
+            }
+            MapObject::AreaObject(area_object) => {
+                if let Some(&c) = area_object.polygon.exterior().0.first() {
+                    area_object.pattern_rotation += self.rotation_difference(c)?;
+                }
+            }
+            MapObject::TextObject(text_object) => {
+                if let Some(rotation) = text_object.rotation.as_mut() {
+                    *rotation += self.rotation_difference(text_object.point.0)?;
+                }
+            }
+            MapObject::LineObject(_) => (),
+        }
+        obj.try_for_each_coord(|c| {
+            self.project(c)?;
+            if let Some(geographic) = &self.geographic {
+                transform(&self.to, geographic, c)?;
+                c.x = c.x.to_degrees();
+                c.y = c.y.to_degrees();
+            }
+            *c = *c + self.offset;
+            Ok(())
+        })
+    }
+
+    // project a coordinate from the source CRS to the CRS of the map, geographic coordinates are in degrees
+    fn project(&self, c: &mut Coord) -> OmapResult<()> {
+        if self.from.is_latlong() {
+            c.x = c.x.to_radians();
+            c.y = c.y.to_radians();
+        }
+        transform(&self.from, &self.to, c)?;
+        Ok(())
+    }
+
+    // the angle of the x-axis of the source CRS in the CRS of the map at `c`
+    fn rotation_difference(&self, c: Coord) -> OmapResult<f64> {
+        // a small step in degrees or in the units of the source CRS
+        let step = if self.from.is_latlong() { 1e-5 } else { 1. };
+
+        let mut start = c;
+        let mut end = c + Coord { x: step, y: 0. };
+        self.project(&mut start)?;
+        self.project(&mut end)?;
+
+        let delta = end - start;
+        Ok(delta.y.atan2(delta.x))
+    }
+}
@@ -1,18 +1,95 @@
-/// Map scale
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Scale {
+use crate::symbols::SymbolSet;
+
+/// Map scale given by its denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Scale(u32);
+
+impl Scale {
+    /// 1:4_000
+    pub const S4_000: Scale = Scale(4_000);
+    /// 1:5_000
+    pub const S5_000: Scale = Scale(5_000);
+    /// 1:7_500
+    pub const S7_500: Scale = Scale(7_500);
     /// 1:10_000
-    S10_000,
+    pub const S10_000: Scale = Scale(10_000);
     /// 1:15_000
-    S15_000,
+    pub const S15_000: Scale = Scale(15_000);
+    /// 1:20_000
+    pub const S20_000: Scale = Scale(20_000);
+
+    /// Create the map scale 1:`denominator`, None if the denominator is 0
+    pub const fn new(denominator: u32) -> Option<Scale> {
+        match denominator {
+            0 => None,
+            d => Some(Scale(d)),
+        }
+    }
+
+    /// Get the scale denominator
+    pub fn denominator(&self) -> u32 {
+        self.0
+    }
+
+    /// The symbol set written by default for the scale  
+    /// ISOM 2017-2 with 1:15_000 dimensions for 1:15_000 and smaller scales and enlarged to 150% for larger scales.
+    /// Sprint maps are written with ISSprOM 2019-2 by setting it with [Omap::set_symbol_set](crate::Omap::set_symbol_set)
+    pub fn default_symbol_set(&self) -> SymbolSet {
+        if self.0 >= 15_000 {
+            SymbolSet::Isom2017_2
+        } else {
+            SymbolSet::Isom2017_2Enlarged
+        }
+    }
+
+    // 1 map unit is 0.001mm on paper => 1000 mu = 1mm on map = 15m on ground at 1:15_000
+    pub(crate) fn map_units_per_meter(&self) -> f64 {
+        1_000_000. / self.0 as f64
+    }
+
+    // the factor a length on the ground is scaled with compared to the same length on paper at the `base` scale
+    pub(crate) fn factor_from(&self, base: Scale) -> f64 {
+        self.0 as f64 / base.0 as f64
+    }
 }
 
 use std::fmt;
 impl fmt::Display for Scale {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Scale::S10_000 => write!(f, "10000"),
-            Scale::S15_000 => write!(f, "15000"),
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scale;
+    use crate::symbols::SymbolSet;
+
+    #[test]
+    fn default_symbol_sets() {
+        for (denominator, symbol_set) in [
+            (4_000, SymbolSet::Isom2017_2Enlarged),
+            (5_000, SymbolSet::Isom2017_2Enlarged),
+            (10_000, SymbolSet::Isom2017_2Enlarged),
+            (14_999, SymbolSet::Isom2017_2Enlarged),
+            (15_000, SymbolSet::Isom2017_2),
+            (20_000, SymbolSet::Isom2017_2),
+        ] {
+            let scale = Scale::new(denominator).unwrap();
+            assert_eq!(scale.default_symbol_set(), symbol_set, "1:{denominator}");
         }
     }
+
+    #[test]
+    fn map_units_per_meter() {
+        assert_eq!(Scale::S15_000.map_units_per_meter(), 1_000_000. / 15_000.);
+        assert_eq!(Scale::S10_000.map_units_per_meter(), 100.);
+        assert_eq!(Scale::S4_000.map_units_per_meter(), 250.);
+        assert_eq!(
+            Scale::S10_000.factor_from(Scale::S15_000),
+            10_000. / 15_000.
+        );
+        assert!(Scale::new(0).is_none());
+        assert_eq!(Scale::new(7_500), Some(Scale::S7_500));
+    }
 }
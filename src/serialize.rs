@@ -12,10 +12,6 @@ trait MapCoord {
     ) -> OmapResult<(i32, i32)>;
 }
 
-// 1 map unit is 0.001mm on paper => 1000 mu = 1mm on map = 15m on ground
-const CONVERSION_15000: f64 = 1_000. / 15.;
-const CONVERSION_10000: f64 = 1_000. / 10.;
-
 const MAX_MU: f64 = i32::MAX as f64;
 
 impl MapCoord for Coord {
@@ -31,16 +27,8 @@ impl MapCoord for Coord {
         let x = self.x * cos - self.y * sin;
         let y = self.x * sin + self.y * cos;
 
-        let (x, y) = match scale {
-            Scale::S10_000 => (
-                (x * CONVERSION_10000 * inv_combined_scale_factor).round(),
-                -(y * CONVERSION_10000 * inv_combined_scale_factor).round(),
-            ),
-            Scale::S15_000 => (
-                (x * CONVERSION_15000 * inv_combined_scale_factor).round(),
-                -(y * CONVERSION_15000 * inv_combined_scale_factor).round(),
-            ),
-        };
+        let conversion = scale.map_units_per_meter() * inv_combined_scale_factor;
+        let (x, y) = ((x * conversion).round(), -(y * conversion).round());
 
         if (x.abs() > MAX_MU) || (y.abs() > MAX_MU) {
             Err(OmapError::MapCoordinateOverflow)
@@ -52,7 +40,7 @@ impl MapCoord for Coord {
 
 pub(crate) trait SerializePolyLine {
     fn serialize_polyline(
-        self,
+        &self,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
@@ -61,7 +49,7 @@ pub(crate) trait SerializePolyLine {
 
 pub(crate) trait SerializeBezier {
     fn serialize_bezier(
-        self,
+        &self,
         bezier_error: f64,
         scale: Scale,
         grivation: f64,
@@ -71,7 +59,7 @@ pub(crate) trait SerializeBezier {
 
 impl SerializePolyLine for LineString {
     fn serialize_polyline(
-        self,
+        &self,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
@@ -108,14 +96,14 @@ impl SerializePolyLine for LineString {
 
 impl SerializeBezier for LineString {
     fn serialize_bezier(
-        self,
+        &self,
         bezier_error: f64,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
     ) -> OmapResult<(Vec<u8>, usize)> {
         let is_closed = self.is_closed();
-        let bezier = BezierString::from_linestring(self, bezier_error);
+        let bezier = BezierString::from_linestring(self.clone(), bezier_error);
 
         let num_coords = bezier.num_points();
         let num_segments = bezier.0.len();
@@ -205,17 +193,16 @@ impl SerializeBezier for LineString {
 
 impl SerializePolyLine for Polygon {
     fn serialize_polyline(
-        self,
+        &self,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
     ) -> OmapResult<(Vec<u8>, usize)> {
-        let (exterior, interiors) = self.into_inner();
-
         let (mut bytes_vec, mut num_coords) =
-            exterior.serialize_polyline(scale, grivation, inv_combined_scale_factor)?;
+            self.exterior()
+                .serialize_polyline(scale, grivation, inv_combined_scale_factor)?;
 
-        for hole in interiors {
+        for hole in self.interiors() {
             let (hv, hc) = hole.serialize_polyline(scale, grivation, inv_combined_scale_factor)?;
             bytes_vec.extend(hv);
             num_coords += hc;
@@ -226,18 +213,20 @@ impl SerializePolyLine for Polygon {
 
 impl SerializeBezier for Polygon {
     fn serialize_bezier(
-        self,
+        &self,
         bezier_error: f64,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
     ) -> OmapResult<(Vec<u8>, usize)> {
-        let (exterior, interiors) = self.into_inner();
-
-        let (mut bytes_vec, mut num_coords) =
-            exterior.serialize_bezier(bezier_error, scale, grivation, inv_combined_scale_factor)?;
+        let (mut bytes_vec, mut num_coords) = self.exterior().serialize_bezier(
+            bezier_error,
+            scale,
+            grivation,
+            inv_combined_scale_factor,
+        )?;
 
-        for hole in interiors {
+        for hole in self.interiors() {
             let (hv, hc) =
                 hole.serialize_bezier(bezier_error, scale, grivation, inv_combined_scale_factor)?;
             bytes_vec.extend(hv);
@@ -249,7 +238,7 @@ impl SerializeBezier for Polygon {
 
 impl SerializePolyLine for Point {
     fn serialize_polyline(
-        self,
+        &self,
         scale: Scale,
         grivation: f64,
         inv_combined_scale_factor: f64,
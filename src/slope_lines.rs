@@ -0,0 +1,133 @@
+use geo_types::{Coord, LineString, Point};
+
+use crate::{
+    contour_loop::ContourLoop,
+    coordinate_mode::ToRelative,
+    geometry::{line_string_length, line_string_signed_area, measured_object},
+    map_part::MapPart,
+    objects::{MapObject, PointObject},
+    symbols::{LineSymbol, PointSymbol, SprintLineSymbol, SprintPointSymbol, Symbol},
+    OmapResult,
+};
+
+/// Add slope lines inside the depression contours of the parts, see [Omap::add_depression_slope_lines](crate::Omap::add_depression_slope_lines)
+pub(crate) fn add_depression_slope_lines(
+    parts: &mut [MapPart],
+    to_relative: &ToRelative,
+    spacing: f64,
+) -> OmapResult<usize> {
+    let keys = [
+        (LineSymbol::Contour, PointSymbol::SlopeLineContour),
+        (LineSymbol::IndexContour, PointSymbol::SlopeLineContour),
+        (LineSymbol::FormLine, PointSymbol::SlopeLineFormLine),
+        (
+            LineSymbol::Sprint(SprintLineSymbol::Contour),
+            PointSymbol::Sprint(SprintPointSymbol::SlopeLineContour),
+        ),
+        (
+            LineSymbol::Sprint(SprintLineSymbol::IndexContour),
+            PointSymbol::Sprint(SprintPointSymbol::SlopeLineContour),
+        ),
+        (
+            LineSymbol::Sprint(SprintLineSymbol::FormLine),
+            PointSymbol::Sprint(SprintPointSymbol::SlopeLineFormLine),
+        ),
+    ];
+    let symbols = keys.map(|(line, _)| Symbol::Line(line));
+
+    let mut num_slope_lines = 0;
+    for part in parts.iter_mut() {
+        let loops = ContourLoop::collect(part, &symbols, to_relative)?;
+
+        let mut slope_lines = Vec::new();
+        for (line_symbol, point_symbol) in keys {
+            let Some(contours) = part.objects.get(&Symbol::Line(line_symbol)) else {
+                continue;
+            };
+            for contour in contours {
+                let MapObject::LineObject(o) = contour else {
+                    continue;
+                };
+                if !o.line.is_closed() || o.line.0.len() < 4 {
+                    continue;
+                }
+                let measured = measured_object(to_relative, contour)?;
+                let MapObject::LineObject(measured_line) = measured.as_ref() else {
+                    continue;
+                };
+                let is_depression = match ContourLoop::relief(&loops, &measured) {
+                    Some(relief) => relief < 0.,
+                    None => line_string_signed_area(&measured_line.line) < 0.,
+                };
+                if !is_depression {
+                    continue;
+                }
+
+                for (i, rotation) in slope_line_placements(&measured_line.line, spacing) {
+                    slope_lines.push(MapObject::PointObject(PointObject::from_point(
+                        Point(o.line.0[i]),
+                        point_symbol,
+                        rotation,
+                    )));
+                }
+            }
+        }
+        num_slope_lines += slope_lines.len();
+        for slope_line in slope_lines {
+            part.add_object(slope_line);
+        }
+    }
+    Ok(num_slope_lines)
+}
+
+// the vertex indices and rotations of slope lines on a closed contour, pointing to its inside.
+// The loop is divided into arcs of about `spacing` length and a slope line is placed at the straightest vertex of each arc
+fn slope_line_placements(line: &LineString, spacing: f64) -> Vec<(usize, f64)> {
+    // the ring without its closing point
+    let ring = &line.0[..line.0.len() - 1];
+    let n = ring.len();
+    let winding = line_string_signed_area(line).signum();
+
+    let length = line_string_length(line);
+    let num_arcs = ((length / spacing).round() as usize).clamp(1, n);
+
+    // the turning angle at every vertex, measured over two neighbours on dense lines
+    let k = if n >= 8 { 2 } else { 1 };
+    let turning = |i: usize| {
+        let (prev, next) = (ring[(i + n - k) % n], ring[(i + k) % n]);
+        let (a, b) = (ring[i] - prev, next - ring[i]);
+        (a.x * b.y - a.y * b.x).atan2(a.x * b.x + a.y * b.y).abs()
+    };
+
+    let mut placements = Vec::with_capacity(num_arcs);
+    let mut distance = 0.;
+    let mut arc_start = 0;
+    for arc in 0..num_arcs {
+        let arc_end = length * (arc + 1) as f64 / num_arcs as f64;
+        let mut best: Option<(usize, f64)> = None;
+        let mut i = arc_start;
+        while i < n && (distance < arc_end || i == arc_start) {
+            let t = turning(i);
+            if best.is_none_or(|(_, b)| t < b) {
+                best = Some((i, t));
+            }
+            let d = ring[(i + 1) % n] - ring[i];
+            distance += d.x.hypot(d.y);
+            i += 1;
+        }
+        arc_start = i;
+        let Some((i, _)) = best else {
+            break;
+        };
+
+        // the normal to the left of the direction of the line points inside counter clockwise loops
+        let tangent = ring[(i + 1) % n] - ring[(i + n - 1) % n];
+        let inside = Coord {
+            x: -tangent.y * winding,
+            y: tangent.x * winding,
+        };
+        // the slope line symbol points up unrotated and is rotated counter clockwise
+        placements.push((i, (-inside.x).atan2(inside.y)));
+    }
+    placements
+}
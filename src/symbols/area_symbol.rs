@@ -1,4 +1,4 @@
-use super::SymbolTrait;
+use super::{PointSymbol, SprintAreaSymbol, SymbolTrait};
 use crate::Scale;
 
 /// Symbols for area objects
@@ -61,14 +61,117 @@ pub enum AreaSymbol {
     MagneticNorthBlack,
     MagneticNorthBlue,
     OutOfBounds,
+    /// ISSprOM 2019-2 area symbols
+    Sprint(SprintAreaSymbol),
+    /// A symbol of a [CustomSymbolSet](super::CustomSymbolSet) by its id in the set
+    Custom(u16),
+}
+
+impl AreaSymbol {
+    /// Get the symbol with the given id in the symbol_x.txt files
+    pub(crate) fn from_id(id: u16) -> Option<Self> {
+        match id {
+            21 => Some(AreaSymbol::BrokenGround),
+            23 => Some(AreaSymbol::VeryBrokenGround),
+            38 => Some(AreaSymbol::GiganticBoulder),
+            41 => Some(AreaSymbol::BoulderField),
+            44 => Some(AreaSymbol::DenseBoulderField),
+            45 => Some(AreaSymbol::StonyGroundSlow),
+            47 => Some(AreaSymbol::StonyGroundWalk),
+            48 => Some(AreaSymbol::StonyGroundFight),
+            49 => Some(AreaSymbol::SandyGround),
+            50 => Some(AreaSymbol::BareRock),
+            52 => Some(AreaSymbol::UncrossableWaterWithBankLine),
+            53 => Some(AreaSymbol::UncrossableWaterWithoutBankLine),
+            54 => Some(AreaSymbol::UncrossableWaterDominantWithBankLine),
+            55 => Some(AreaSymbol::UncrossableWaterDominantWithoutBankLine),
+            57 => Some(AreaSymbol::ShallowWaterWithSolidBankLine),
+            58 => Some(AreaSymbol::ShallowWaterWithDashedBankLine),
+            59 => Some(AreaSymbol::ShallowWaterWithoutBankLine),
+            62 => Some(AreaSymbol::SmallShallowWater),
+            67 => Some(AreaSymbol::UncrossableMarshWithBankLine),
+            68 => Some(AreaSymbol::UncrossableMarshWithoutBankLine),
+            69 => Some(AreaSymbol::Marsh),
+            72 => Some(AreaSymbol::IndistinctMarsh),
+            77 => Some(AreaSymbol::OpenLand),
+            78 => Some(AreaSymbol::OpenLandScatteredTrees),
+            79 => Some(AreaSymbol::OpenLandScatteredBushes),
+            80 => Some(AreaSymbol::RoughOpenLand),
+            81 => Some(AreaSymbol::RoughOpenLandScatteredTrees),
+            82 => Some(AreaSymbol::RoughOpenLandScatteredBushes),
+            83 => Some(AreaSymbol::Forest),
+            84 => Some(AreaSymbol::LightGreen),
+            85 => Some(AreaSymbol::LightGreenOneDirectionWhite),
+            86 => Some(AreaSymbol::UnderGrowth),
+            87 => Some(AreaSymbol::MediumGreen),
+            88 => Some(AreaSymbol::MediumGreenOneDirectionWhite),
+            89 => Some(AreaSymbol::MediumGreenOneDirectionLightGreen),
+            90 => Some(AreaSymbol::DenseUnderGrowth),
+            91 => Some(AreaSymbol::DarkGreen),
+            92 => Some(AreaSymbol::DarkGreenOneDirectionWhite),
+            93 => Some(AreaSymbol::DarkGreenOneDirectionLightGreen),
+            94 => Some(AreaSymbol::DarkGreenOneDirectionMediumGreen),
+            96 => Some(AreaSymbol::CultivatedLand),
+            97 => Some(AreaSymbol::Orchard),
+            98 => Some(AreaSymbol::RoughOrchard),
+            99 => Some(AreaSymbol::Vineyard),
+            100 => Some(AreaSymbol::RoughVineyard),
+            107 => Some(AreaSymbol::PavedAreaWithBoundary),
+            108 => Some(AreaSymbol::PavedAreaWithoutBoundary),
+            139 => Some(AreaSymbol::PrivateArea),
+            141 => Some(AreaSymbol::Building),
+            143 => Some(AreaSymbol::LargeBuildingWithOutline),
+            144 => Some(AreaSymbol::LargeBuildingWithoutOutline),
+            146 => Some(AreaSymbol::CanopyWithOutline),
+            147 => Some(AreaSymbol::CanopyWithoutOutline),
+            160 => Some(AreaSymbol::MagneticNorthBlack),
+            161 => Some(AreaSymbol::MagneticNorthBlue),
+            167 => Some(AreaSymbol::OutOfBounds),
+            _ => None,
+        }
+    }
+
+    /// The point symbol an object smaller than the minimum size of the symbol is drawn with, if there is one
+    pub fn min_size_point_symbol(&self) -> Option<PointSymbol> {
+        match self {
+            AreaSymbol::Sprint(s) => s.min_size_point_symbol(),
+            AreaSymbol::BrokenGround | AreaSymbol::VeryBrokenGround => {
+                Some(PointSymbol::BrokenGroundSingleDot)
+            }
+            AreaSymbol::GiganticBoulder => Some(PointSymbol::LargeBoulder),
+            AreaSymbol::BoulderField | AreaSymbol::DenseBoulderField => {
+                Some(PointSymbol::BoulderFieldSingleTriangle)
+            }
+            AreaSymbol::StonyGroundSlow
+            | AreaSymbol::StonyGroundWalk
+            | AreaSymbol::StonyGroundFight => Some(PointSymbol::StonyGroundSingleDot),
+            AreaSymbol::UncrossableWaterWithBankLine
+            | AreaSymbol::UncrossableWaterWithoutBankLine
+            | AreaSymbol::UncrossableWaterDominantWithBankLine
+            | AreaSymbol::UncrossableWaterDominantWithoutBankLine
+            | AreaSymbol::ShallowWaterWithSolidBankLine
+            | AreaSymbol::ShallowWaterWithDashedBankLine
+            | AreaSymbol::ShallowWaterWithoutBankLine
+            | AreaSymbol::SmallShallowWater => Some(PointSymbol::Waterhole),
+            AreaSymbol::UncrossableMarshWithBankLine
+            | AreaSymbol::UncrossableMarshWithoutBankLine
+            | AreaSymbol::Marsh => Some(PointSymbol::MinimumMarsh),
+            AreaSymbol::IndistinctMarsh => Some(PointSymbol::MinimumIndistinctMarsh),
+            AreaSymbol::Building
+            | AreaSymbol::LargeBuildingWithOutline
+            | AreaSymbol::LargeBuildingWithoutOutline => Some(PointSymbol::MinimumBuilding),
+            _ => None,
+        }
+    }
 }
 
 impl SymbolTrait for AreaSymbol {
     // in square meters
     fn min_size(&self, scale: Scale) -> f64 {
-        const CONVERSION: f64 = 4. / 9.;
-
+        // minimum sizes at 1:15_000
         let min = match self {
+            AreaSymbol::Sprint(s) => return s.min_size(scale),
+            AreaSymbol::Custom(_) => return 0.,
             AreaSymbol::BrokenGround => 100.,
             AreaSymbol::VeryBrokenGround => 49.,
             AreaSymbol::GiganticBoulder => 67.,
@@ -124,14 +227,13 @@ impl SymbolTrait for AreaSymbol {
             AreaSymbol::OutOfBounds => 2025.,
             _ => 0.,
         };
-        match scale {
-            Scale::S10_000 => min * CONVERSION,
-            Scale::S15_000 => min,
-        }
+        min * scale.factor_from(Scale::S15_000).powi(2)
     }
 
-    fn id(&self) -> u8 {
+    fn id(&self) -> u16 {
         match self {
+            AreaSymbol::Sprint(s) => s.id(),
+            AreaSymbol::Custom(id) => *id,
             AreaSymbol::BrokenGround => 21,
             AreaSymbol::VeryBrokenGround => 23,
             AreaSymbol::GiganticBoulder => 38,
@@ -192,16 +294,27 @@ impl SymbolTrait for AreaSymbol {
     }
 
     fn is_rotatable(&self) -> bool {
-        matches!(
-            self,
-            AreaSymbol::DarkGreenOneDirectionMediumGreen
-                | AreaSymbol::DarkGreenOneDirectionLightGreen
-                | AreaSymbol::DarkGreenOneDirectionWhite
-                | AreaSymbol::MediumGreenOneDirectionLightGreen
-                | AreaSymbol::MediumGreenOneDirectionWhite
-                | AreaSymbol::LightGreenOneDirectionWhite
-                | AreaSymbol::RoughVineyard
-                | AreaSymbol::Vineyard
-        )
+        match self {
+            AreaSymbol::Sprint(s) => s.is_rotatable(),
+            // Mapper ignores the rotation of symbols that are not rotatable
+            AreaSymbol::Custom(_) => true,
+            _ => matches!(
+                self,
+                AreaSymbol::DarkGreenOneDirectionMediumGreen
+                    | AreaSymbol::DarkGreenOneDirectionLightGreen
+                    | AreaSymbol::DarkGreenOneDirectionWhite
+                    | AreaSymbol::MediumGreenOneDirectionLightGreen
+                    | AreaSymbol::MediumGreenOneDirectionWhite
+                    | AreaSymbol::LightGreenOneDirectionWhite
+                    | AreaSymbol::RoughVineyard
+                    | AreaSymbol::Vineyard
+            ),
+        }
+    }
+}
+
+impl From<SprintAreaSymbol> for AreaSymbol {
+    fn from(value: SprintAreaSymbol) -> Self {
+        AreaSymbol::Sprint(value)
     }
 }
@@ -0,0 +1,293 @@
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use std::{collections::HashMap, path::Path};
+
+use super::{AreaSymbol, LineSymbol, PointSymbol, Symbol, SymbolTrait, TextSymbol};
+use crate::{
+    deserialize::{attribute, parse_attribute},
+    OmapError, OmapResult,
+};
+
+// symbol type codes as defined by OpenOrienteering Mapper
+const POINT_SYMBOL: u8 = 1;
+const LINE_SYMBOL: u8 = 2;
+const AREA_SYMBOL: u8 = 4;
+const TEXT_SYMBOL: u8 = 8;
+const COMBINED_SYMBOL: u8 = 16;
+
+/// A symbol set with its color table loaded at runtime from an existing .omap, .xmap or .xml file
+///
+/// The symbols are addressed by their code or name with [Self::symbol] and are represented by the `Custom` variants of the symbol enums
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomSymbolSet {
+    name: String,
+    colors: String,
+    symbols: String,
+    entries: Vec<SymbolEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SymbolEntry {
+    symbol: Symbol,
+    code: String,
+    name: String,
+    // the priority of the top-most color of the symbol, lower is drawn on top
+    priority: u16,
+}
+
+impl CustomSymbolSet {
+    /// Load the colors and symbols of a map or symbol set file
+    pub fn from_file(path: impl AsRef<Path>) -> OmapResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_xml(&content)
+    }
+
+    /// Load the colors and symbols of the xml content of a map or symbol set file
+    pub fn from_xml(content: &str) -> OmapResult<Self> {
+        let mut reader = Reader::from_str(content);
+
+        let mut colors = None;
+        let mut symbols = None;
+        let mut name = String::new();
+        let mut definitions = Vec::new();
+
+        let mut buf = Vec::new();
+        loop {
+            let start = reader.buffer_position() as usize;
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => match e.name().as_ref() {
+                    b"colors" => {
+                        let _ = reader.read_to_end_into(e.name(), &mut Vec::new())?;
+                        colors =
+                            Some(content[start..reader.buffer_position() as usize].to_string());
+                    }
+                    b"symbols" => {
+                        name = attribute(&e, "id")?.unwrap_or_default();
+                        read_symbols(&mut reader, &mut definitions)?;
+                        symbols =
+                            Some(content[start..reader.buffer_position() as usize].to_string());
+                    }
+                    b"parts" | b"templates" | b"view" => {
+                        let _ = reader.read_to_end_into(e.name(), &mut Vec::new())?;
+                    }
+                    _ => (),
+                },
+                Event::Eof => break,
+                _ => (),
+            }
+            buf.clear();
+        }
+
+        let (Some(colors), Some(symbols)) = (colors, symbols) else {
+            return Err(OmapError::InvalidFile(
+                "the colors or symbols are missing".to_string(),
+            ));
+        };
+
+        let types: HashMap<u16, u8> = definitions.iter().map(|d| (d.id, d.kind)).collect();
+        let own_priorities: HashMap<u16, u16> = definitions
+            .iter()
+            .map(|d| (d.id, d.colors.iter().copied().min().unwrap_or(u16::MAX)))
+            .collect();
+        let entries = definitions
+            .into_iter()
+            .filter_map(|d| {
+                let symbol = match d.kind {
+                    POINT_SYMBOL => Symbol::Point(PointSymbol::Custom(d.id)),
+                    LINE_SYMBOL => Symbol::Line(LineSymbol::Custom(d.id)),
+                    AREA_SYMBOL => Symbol::Area(AreaSymbol::Custom(d.id)),
+                    TEXT_SYMBOL => Symbol::Text(TextSymbol::Custom(d.id)),
+                    // combined symbols are used for areas if any of their parts is an area symbol
+                    COMBINED_SYMBOL => {
+                        if d.parts.iter().any(|p| match p {
+                            Part::Private(kind) => *kind == AREA_SYMBOL,
+                            Part::Reference(id) => types.get(id) == Some(&AREA_SYMBOL),
+                        }) {
+                            Symbol::Area(AreaSymbol::Custom(d.id))
+                        } else {
+                            Symbol::Line(LineSymbol::Custom(d.id))
+                        }
+                    }
+                    _ => return None,
+                };
+                // combined symbols are drawn with the colors of their referenced parts as well
+                let priority = d
+                    .parts
+                    .iter()
+                    .filter_map(|p| match p {
+                        Part::Reference(id) => own_priorities.get(id).copied(),
+                        Part::Private(_) => None,
+                    })
+                    .chain(own_priorities.get(&d.id).copied())
+                    .min()
+                    .unwrap_or(u16::MAX);
+                Some(SymbolEntry {
+                    symbol,
+                    code: d.code,
+                    name: d.name,
+                    priority,
+                })
+            })
+            .collect();
+
+        Ok(CustomSymbolSet {
+            name,
+            colors,
+            symbols,
+            entries,
+        })
+    }
+
+    /// The id of the symbol set as given in the file
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get a symbol by its code (e.g. "101" or "401.1") or by its name (e.g. "Contour")
+    pub fn symbol(&self, code_or_name: &str) -> Option<Symbol> {
+        self.entries
+            .iter()
+            .find(|e| e.code == code_or_name)
+            .or_else(|| self.entries.iter().find(|e| e.name == code_or_name))
+            .map(|e| e.symbol)
+    }
+
+    /// Iterate over the symbols of the set with their codes and names
+    pub fn symbols(&self) -> impl Iterator<Item = (Symbol, &str, &str)> {
+        self.entries
+            .iter()
+            .map(|e| (e.symbol, e.code.as_str(), e.name.as_str()))
+    }
+
+    /// The number of symbols in the set
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the set has no symbols
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn contains(&self, symbol: Symbol) -> bool {
+        self.entries.iter().any(|e| e.symbol == symbol)
+    }
+
+    /// The priority of the top-most color of the symbol with the given id, lower is drawn on top
+    pub(crate) fn priority_from_id(&self, id: u16) -> Option<u16> {
+        self.entries
+            .iter()
+            .find(|e| e.symbol.id() == id)
+            .map(|e| e.priority)
+    }
+
+    /// Whether both sets have the same symbols with the same ids, types and codes, no matter their names or colors
+    pub(crate) fn same_symbols(&self, other: &CustomSymbolSet) -> bool {
+        self.entries.len() == other.entries.len()
+            && self
+                .entries
+                .iter()
+                .zip(other.entries.iter())
+                .all(|(a, b)| a.symbol == b.symbol && a.code == b.code)
+    }
+
+    pub(crate) fn symbol_from_id(&self, id: u16) -> Option<Symbol> {
+        self.entries.iter().map(|e| e.symbol).find(|s| s.id() == id)
+    }
+
+    pub(crate) fn colors_xml(&self) -> &str {
+        &self.colors
+    }
+
+    pub(crate) fn symbols_xml(&self) -> &str {
+        &self.symbols
+    }
+}
+
+enum Part {
+    // the type of a private part symbol
+    Private(u8),
+    // the id of a referenced symbol
+    Reference(u16),
+}
+
+struct Definition {
+    id: u16,
+    kind: u8,
+    code: String,
+    name: String,
+    parts: Vec<Part>,
+    // the priorities of the colors used by the symbol and its private parts
+    colors: Vec<u16>,
+}
+
+// read the symbols, the reader must be positioned right after the <symbols> start tag
+fn read_symbols(reader: &mut Reader<&[u8]>, definitions: &mut Vec<Definition>) -> OmapResult<()> {
+    let mut buf = Vec::new();
+    let mut depth = 0;
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if e.name().as_ref() == b"symbol" => {
+                if depth == 0 {
+                    let (Some(id), Some(kind)) = (
+                        parse_attribute::<u16>(&e, "id")?,
+                        parse_attribute::<u8>(&e, "type")?,
+                    ) else {
+                        return Err(OmapError::InvalidFile(
+                            "a symbol is missing its id or type".to_string(),
+                        ));
+                    };
+                    definitions.push(Definition {
+                        id,
+                        kind,
+                        code: attribute(&e, "code")?.unwrap_or_default(),
+                        name: attribute(&e, "name")?.unwrap_or_default(),
+                        parts: Vec::new(),
+                        colors: Vec::new(),
+                    });
+                } else if let (Some(kind), Some(d)) =
+                    (parse_attribute::<u8>(&e, "type")?, definitions.last_mut())
+                {
+                    d.parts.push(Part::Private(kind));
+                }
+                depth += 1;
+            }
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"part" => {
+                if let (Some(id), Some(d)) = (
+                    parse_attribute::<u16>(&e, "symbol")?,
+                    definitions.last_mut(),
+                ) {
+                    d.parts.push(Part::Reference(id));
+                }
+            }
+            Event::Start(e) | Event::Empty(e) => {
+                if let Some(d) = definitions.last_mut() {
+                    d.colors.extend(color_attributes(&e));
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                b"symbol" => depth -= 1,
+                b"symbols" => break,
+                _ => (),
+            },
+            Event::Eof => {
+                return Err(OmapError::InvalidFile(
+                    "unexpected end of file in symbols".to_string(),
+                ))
+            }
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(())
+}
+
+// the values of all color attributes (color, inner_color, outer_color, ...) of an element, -1 means no color
+fn color_attributes<'a>(e: &'a BytesStart<'_>) -> impl Iterator<Item = u16> + 'a {
+    e.attributes()
+        .flatten()
+        .filter(|a| a.key.as_ref().ends_with(b"color"))
+        .filter_map(|a| std::str::from_utf8(&a.value).ok()?.parse().ok())
+}
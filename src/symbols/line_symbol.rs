@@ -1,4 +1,4 @@
-use super::SymbolTrait;
+use super::{PointSymbol, SprintLineSymbol, SymbolTrait};
 use crate::Scale;
 
 /// Symbols for line objects
@@ -69,13 +69,126 @@ pub enum LineSymbol {
     ImpassableProminentLinearFeature,
     Stairway,
     SimpleOrienteeringCourse,
+    /// ISSprOM 2019-2 line symbols
+    Sprint(SprintLineSymbol),
+    /// A symbol of a [CustomSymbolSet](super::CustomSymbolSet) by its id in the set
+    Custom(u16),
+}
+
+impl LineSymbol {
+    /// Get the symbol with the given id in the symbol_x.txt files
+    pub(crate) fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(LineSymbol::Contour),
+            2 => Some(LineSymbol::BasemapContour),
+            3 => Some(LineSymbol::NegBasemapContour),
+            4 => Some(LineSymbol::IndexContour),
+            6 => Some(LineSymbol::FormLine),
+            8 => Some(LineSymbol::EarthBank),
+            10 => Some(LineSymbol::EarthBankTopLine),
+            11 => Some(LineSymbol::EarthBankTagLine),
+            12 => Some(LineSymbol::EarthWall),
+            13 => Some(LineSymbol::RetainingEarthWall),
+            14 => Some(LineSymbol::RuinedEarthWall),
+            15 => Some(LineSymbol::ErosionGully),
+            16 => Some(LineSymbol::SmallErosionGully),
+            25 => Some(LineSymbol::ImpassableCliff),
+            27 => Some(LineSymbol::ImpassableCliffTopLine),
+            28 => Some(LineSymbol::ImpassableCliffTagLine),
+            29 => Some(LineSymbol::Cliff),
+            31 => Some(LineSymbol::CliffWithTags),
+            51 => Some(LineSymbol::Trench),
+            56 => Some(LineSymbol::BankLine),
+            60 => Some(LineSymbol::ShallowWaterOutline),
+            61 => Some(LineSymbol::ShallowWaterDashedOutline),
+            64 => Some(LineSymbol::CrossableWatercourse),
+            65 => Some(LineSymbol::SmallCrossableWatercourse),
+            66 => Some(LineSymbol::SeasonalWatercourse),
+            71 => Some(LineSymbol::NarrowMarsh),
+            95 => Some(LineSymbol::Hedge),
+            101 => Some(LineSymbol::DistinctCultivationBoundary),
+            102 => Some(LineSymbol::DistinctVegetationBoundary),
+            109 => Some(LineSymbol::PavedAreaBoundingLine),
+            110 => Some(LineSymbol::Road),
+            111 => Some(LineSymbol::RoadDualCarriageway),
+            112 => Some(LineSymbol::GravelRoad),
+            113 => Some(LineSymbol::VehicleTrack),
+            114 => Some(LineSymbol::Footpath),
+            115 => Some(LineSymbol::SmallFootpath),
+            116 => Some(LineSymbol::IndistinctFootpath),
+            117 => Some(LineSymbol::NarrowRide),
+            118 => Some(LineSymbol::NarrowRideEasyRunning),
+            119 => Some(LineSymbol::NarrowRideNormalRunning),
+            120 => Some(LineSymbol::NarrowRideSlowRunning),
+            121 => Some(LineSymbol::NarrowRideWalk),
+            122 => Some(LineSymbol::Railway),
+            123 => Some(LineSymbol::ImpassableRailway),
+            124 => Some(LineSymbol::SmallPowerLine),
+            125 => Some(LineSymbol::LargePowerLine),
+            126 => Some(LineSymbol::MajorPowerLine),
+            127 => Some(LineSymbol::MajorPowerLineWithPylons),
+            128 => Some(LineSymbol::BridgeTunnel),
+            131 => Some(LineSymbol::Wall),
+            132 => Some(LineSymbol::RetainingWall),
+            133 => Some(LineSymbol::RuinedWall),
+            134 => Some(LineSymbol::ImpassableWall),
+            135 => Some(LineSymbol::Fence),
+            136 => Some(LineSymbol::RuinedFence),
+            137 => Some(LineSymbol::ImpassableFence),
+            140 => Some(LineSymbol::PrivateAreaBoundingLine),
+            145 => Some(LineSymbol::LargeBuildingOutline),
+            148 => Some(LineSymbol::CanopyOutline),
+            149 => Some(LineSymbol::Ruin),
+            155 => Some(LineSymbol::ProminentLinearFeature),
+            156 => Some(LineSymbol::ImpassableProminentLinearFeature),
+            159 => Some(LineSymbol::Stairway),
+            166 => Some(LineSymbol::SimpleOrienteeringCourse),
+            _ => None,
+        }
+    }
+
+    /// Check if the symbol is a contour, index contour, form line or basemap contour
+    pub fn is_contour(&self) -> bool {
+        match self {
+            LineSymbol::Sprint(s) => s.is_contour(),
+            _ => matches!(
+                self,
+                LineSymbol::Contour
+                    | LineSymbol::BasemapContour
+                    | LineSymbol::NegBasemapContour
+                    | LineSymbol::IndexContour
+                    | LineSymbol::FormLine
+            ),
+        }
+    }
+
+    /// The point symbol an object shorter than the minimum length of the symbol is drawn with, if there is one
+    pub fn min_size_point_symbol(&self) -> Option<PointSymbol> {
+        match self {
+            LineSymbol::Sprint(s) => s.min_size_point_symbol(),
+            LineSymbol::EarthBank | LineSymbol::EarthBankTopLine => {
+                Some(PointSymbol::MinimumEarthBank)
+            }
+            LineSymbol::ImpassableCliff | LineSymbol::ImpassableCliffTopLine => {
+                Some(PointSymbol::MinimumImpassableCliff)
+            }
+            LineSymbol::Cliff => Some(PointSymbol::MinimumCliff),
+            LineSymbol::CliffWithTags => Some(PointSymbol::MinimumCliffWithTags),
+            LineSymbol::NarrowMarsh => Some(PointSymbol::MinimumMarsh),
+            LineSymbol::BridgeTunnel => Some(PointSymbol::MinimumBridgeTunnel),
+            LineSymbol::Ruin => Some(PointSymbol::MinimumRuin),
+            _ => None,
+        }
+    }
 }
 
 impl SymbolTrait for LineSymbol {
     // in meters
     fn min_size(&self, scale: Scale) -> f64 {
-        const CONVERSION: f64 = 2. / 3.;
+        // minimum lengths at 1:15_000
         let min = match self {
+            LineSymbol::Sprint(s) => return s.min_size(scale),
+            LineSymbol::Custom(_) => return 0.,
             LineSymbol::FormLine => 16.5,
             LineSymbol::EarthBank => 9.,
             LineSymbol::EarthBankTopLine => 9.,
@@ -128,14 +241,13 @@ impl SymbolTrait for LineSymbol {
             _ => 0.,
         };
 
-        match scale {
-            Scale::S10_000 => min * CONVERSION,
-            Scale::S15_000 => min,
-        }
+        min * scale.factor_from(Scale::S15_000)
     }
 
-    fn id(&self) -> u8 {
+    fn id(&self) -> u16 {
         match self {
+            LineSymbol::Sprint(s) => s.id(),
+            LineSymbol::Custom(id) => *id,
             LineSymbol::Contour => 0,
             LineSymbol::BasemapContour => 2,
             LineSymbol::NegBasemapContour => 3,
@@ -203,3 +315,9 @@ impl SymbolTrait for LineSymbol {
         }
     }
 }
+
+impl From<SprintLineSymbol> for LineSymbol {
+    fn from(value: SprintLineSymbol) -> Self {
+        LineSymbol::Sprint(value)
+    }
+}
@@ -1,13 +1,25 @@
 mod area_symbol;
+mod custom_symbol_set;
 mod line_symbol;
 mod point_symbol;
+mod sprint_area_symbol;
+mod sprint_line_symbol;
+mod sprint_point_symbol;
+mod sprint_text_symbol;
 mod symbol;
+mod symbol_set;
 mod text_symbol;
 
 pub use area_symbol::AreaSymbol;
+pub use custom_symbol_set::CustomSymbolSet;
 pub use line_symbol::LineSymbol;
 pub use point_symbol::PointSymbol;
+pub use sprint_area_symbol::SprintAreaSymbol;
+pub use sprint_line_symbol::SprintLineSymbol;
+pub use sprint_point_symbol::SprintPointSymbol;
+pub use sprint_text_symbol::SprintTextSymbol;
 pub use symbol::Symbol;
+pub use symbol_set::SymbolSet;
 pub use text_symbol::TextSymbol;
 
 /// trait defining the three functions all symbol types must have
@@ -22,6 +34,6 @@ pub trait SymbolTrait {
         false
     }
 
-    /// the id of the symbol in the symbol_x.txt files
-    fn id(&self) -> u8;
+    /// the id of the symbol in the symbols_x.txt file of its symbol set
+    fn id(&self) -> u16;
 }
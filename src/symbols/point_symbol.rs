@@ -1,4 +1,4 @@
-use super::SymbolTrait;
+use super::{SprintPointSymbol, SymbolTrait};
 
 /// Symbols for point objects
 #[allow(missing_docs)]
@@ -49,11 +49,71 @@ pub enum PointSymbol {
     RegistrationMark,
     SpotHeight,
     OpenOrienteeringMapperLogo,
+    /// ISSprOM 2019-2 point symbols
+    Sprint(SprintPointSymbol),
+    /// A symbol of a [CustomSymbolSet](super::CustomSymbolSet) by its id in the set
+    Custom(u16),
+}
+
+impl PointSymbol {
+    /// Get the symbol with the given id in the symbol_x.txt files
+    pub(crate) fn from_id(id: u16) -> Option<Self> {
+        match id {
+            1 => Some(PointSymbol::SlopeLineContour),
+            7 => Some(PointSymbol::SlopeLineFormLine),
+            9 => Some(PointSymbol::MinimumEarthBank),
+            17 => Some(PointSymbol::DotKnoll),
+            18 => Some(PointSymbol::ElongatedDotKnoll),
+            19 => Some(PointSymbol::UDepression),
+            20 => Some(PointSymbol::Pit),
+            22 => Some(PointSymbol::BrokenGroundSingleDot),
+            24 => Some(PointSymbol::ProminentLandFeature),
+            26 => Some(PointSymbol::MinimumImpassableCliff),
+            30 => Some(PointSymbol::MinimumCliff),
+            32 => Some(PointSymbol::MinimumCliffWithTags),
+            33 => Some(PointSymbol::RockyPitCave),
+            34 => Some(PointSymbol::DangerousPit),
+            35 => Some(PointSymbol::SmallBoulder),
+            36 => Some(PointSymbol::MediumBoulder),
+            37 => Some(PointSymbol::LargeBoulder),
+            39 => Some(PointSymbol::BoulderCluster),
+            40 => Some(PointSymbol::LargeBoulderCluster),
+            42 => Some(PointSymbol::BoulderFieldSingleTriangle),
+            43 => Some(PointSymbol::BoulderFieldSingleTriangleLarge),
+            46 => Some(PointSymbol::StonyGroundSingleDot),
+            63 => Some(PointSymbol::Waterhole),
+            70 => Some(PointSymbol::MinimumMarsh),
+            73 => Some(PointSymbol::MinimumIndistinctMarsh),
+            74 => Some(PointSymbol::Well),
+            75 => Some(PointSymbol::Spring),
+            76 => Some(PointSymbol::ProminentWaterFeature),
+            104 => Some(PointSymbol::ProminentTree),
+            105 => Some(PointSymbol::ProminentBush),
+            106 => Some(PointSymbol::ProminentVegetationFeature),
+            129 => Some(PointSymbol::MinimumBridgeTunnel),
+            130 => Some(PointSymbol::Footbridge),
+            138 => Some(PointSymbol::FenceCrossingPoint),
+            142 => Some(PointSymbol::MinimumBuilding),
+            150 => Some(PointSymbol::MinimumRuin),
+            151 => Some(PointSymbol::HighTower),
+            152 => Some(PointSymbol::Tower),
+            153 => Some(PointSymbol::Cairn),
+            154 => Some(PointSymbol::FodderRack),
+            157 => Some(PointSymbol::ProminentManMadeFeatureO),
+            158 => Some(PointSymbol::ProminentManMadeFeatureX),
+            162 => Some(PointSymbol::RegistrationMark),
+            163 => Some(PointSymbol::SpotHeight),
+            168 => Some(PointSymbol::OpenOrienteeringMapperLogo),
+            _ => None,
+        }
+    }
 }
 
 impl SymbolTrait for PointSymbol {
-    fn id(&self) -> u8 {
+    fn id(&self) -> u16 {
         match self {
+            PointSymbol::Sprint(s) => s.id(),
+            PointSymbol::Custom(id) => *id,
             PointSymbol::SlopeLineContour => 1,
             PointSymbol::SlopeLineFormLine => 7,
             PointSymbol::MinimumEarthBank => 9,
@@ -103,24 +163,35 @@ impl SymbolTrait for PointSymbol {
     }
 
     fn is_rotatable(&self) -> bool {
-        matches!(
-            self,
-            PointSymbol::BoulderFieldSingleTriangle
-                | PointSymbol::BoulderFieldSingleTriangleLarge
-                | PointSymbol::ElongatedDotKnoll
-                | PointSymbol::FenceCrossingPoint
-                | PointSymbol::Footbridge
-                | PointSymbol::MinimumBridgeTunnel
-                | PointSymbol::MinimumBuilding
-                | PointSymbol::MinimumCliff
-                | PointSymbol::MinimumCliffWithTags
-                | PointSymbol::MinimumEarthBank
-                | PointSymbol::MinimumImpassableCliff
-                | PointSymbol::MinimumRuin
-                | PointSymbol::RockyPitCave
-                | PointSymbol::SlopeLineContour
-                | PointSymbol::SlopeLineFormLine
-                | PointSymbol::Spring
-        )
+        match self {
+            PointSymbol::Sprint(s) => s.is_rotatable(),
+            // Mapper ignores the rotation of symbols that are not rotatable
+            PointSymbol::Custom(_) => true,
+            _ => matches!(
+                self,
+                PointSymbol::BoulderFieldSingleTriangle
+                    | PointSymbol::BoulderFieldSingleTriangleLarge
+                    | PointSymbol::ElongatedDotKnoll
+                    | PointSymbol::FenceCrossingPoint
+                    | PointSymbol::Footbridge
+                    | PointSymbol::MinimumBridgeTunnel
+                    | PointSymbol::MinimumBuilding
+                    | PointSymbol::MinimumCliff
+                    | PointSymbol::MinimumCliffWithTags
+                    | PointSymbol::MinimumEarthBank
+                    | PointSymbol::MinimumImpassableCliff
+                    | PointSymbol::MinimumRuin
+                    | PointSymbol::RockyPitCave
+                    | PointSymbol::SlopeLineContour
+                    | PointSymbol::SlopeLineFormLine
+                    | PointSymbol::Spring
+            ),
+        }
+    }
+}
+
+impl From<SprintPointSymbol> for PointSymbol {
+    fn from(value: SprintPointSymbol) -> Self {
+        PointSymbol::Sprint(value)
     }
 }
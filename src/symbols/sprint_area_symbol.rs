@@ -0,0 +1,193 @@
+use super::{PointSymbol, SprintPointSymbol, SymbolTrait};
+use crate::Scale;
+
+/// ISSprOM 2019-2 symbols for area objects
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SprintAreaSymbol {
+    BrokenGround,
+    GiganticBoulder,
+    BoulderField,
+    StonyGround,
+    BareRock,
+    SandyGround,
+    UncrossableWaterWithBankLine,
+    UncrossableWaterWithoutBankLine,
+    ShallowWaterWithOutline,
+    ShallowWaterWithoutOutline,
+    UncrossableMarshWithOutline,
+    UncrossableMarshWithoutOutline,
+    Marsh,
+    IndistinctMarsh,
+    OpenLand,
+    OpenLandScatteredTrees,
+    RoughOpenLand,
+    RoughOpenLandScatteredTrees,
+    Forest,
+    LightGreen,
+    UnderGrowth,
+    MediumGreen,
+    DenseUnderGrowth,
+    DarkGreen,
+    ImpassableVegetation,
+    CultivatedLand,
+    Orchard,
+    Vineyard,
+    PavedArea,
+    PavedAreaWithBoundary,
+    PrivateArea,
+    Building,
+    CanopyWithOutline,
+    CanopyWithoutOutline,
+    MagneticNorth,
+    OutOfBounds,
+}
+
+impl SprintAreaSymbol {
+    /// Get the symbol with the given id in the symbols_4.txt file
+    pub(crate) fn from_id(id: u16) -> Option<Self> {
+        match id {
+            17 => Some(SprintAreaSymbol::BrokenGround),
+            26 => Some(SprintAreaSymbol::GiganticBoulder),
+            28 => Some(SprintAreaSymbol::BoulderField),
+            29 => Some(SprintAreaSymbol::StonyGround),
+            30 => Some(SprintAreaSymbol::BareRock),
+            31 => Some(SprintAreaSymbol::SandyGround),
+            32 => Some(SprintAreaSymbol::UncrossableWaterWithBankLine),
+            33 => Some(SprintAreaSymbol::UncrossableWaterWithoutBankLine),
+            35 => Some(SprintAreaSymbol::ShallowWaterWithOutline),
+            36 => Some(SprintAreaSymbol::ShallowWaterWithoutOutline),
+            42 => Some(SprintAreaSymbol::UncrossableMarshWithOutline),
+            43 => Some(SprintAreaSymbol::UncrossableMarshWithoutOutline),
+            44 => Some(SprintAreaSymbol::Marsh),
+            47 => Some(SprintAreaSymbol::IndistinctMarsh),
+            51 => Some(SprintAreaSymbol::OpenLand),
+            52 => Some(SprintAreaSymbol::OpenLandScatteredTrees),
+            53 => Some(SprintAreaSymbol::RoughOpenLand),
+            54 => Some(SprintAreaSymbol::RoughOpenLandScatteredTrees),
+            55 => Some(SprintAreaSymbol::Forest),
+            56 => Some(SprintAreaSymbol::LightGreen),
+            57 => Some(SprintAreaSymbol::UnderGrowth),
+            58 => Some(SprintAreaSymbol::MediumGreen),
+            59 => Some(SprintAreaSymbol::DenseUnderGrowth),
+            60 => Some(SprintAreaSymbol::DarkGreen),
+            61 => Some(SprintAreaSymbol::ImpassableVegetation),
+            62 => Some(SprintAreaSymbol::CultivatedLand),
+            63 => Some(SprintAreaSymbol::Orchard),
+            64 => Some(SprintAreaSymbol::Vineyard),
+            70 => Some(SprintAreaSymbol::PavedArea),
+            71 => Some(SprintAreaSymbol::PavedAreaWithBoundary),
+            90 => Some(SprintAreaSymbol::PrivateArea),
+            92 => Some(SprintAreaSymbol::Building),
+            94 => Some(SprintAreaSymbol::CanopyWithOutline),
+            95 => Some(SprintAreaSymbol::CanopyWithoutOutline),
+            108 => Some(SprintAreaSymbol::MagneticNorth),
+            113 => Some(SprintAreaSymbol::OutOfBounds),
+            _ => None,
+        }
+    }
+
+    /// The point symbol an object smaller than the minimum size of the symbol is drawn with, if there is one
+    pub fn min_size_point_symbol(&self) -> Option<PointSymbol> {
+        let point = match self {
+            SprintAreaSymbol::GiganticBoulder => SprintPointSymbol::LargeBoulder,
+            SprintAreaSymbol::BoulderField => SprintPointSymbol::BoulderCluster,
+            SprintAreaSymbol::UncrossableMarshWithOutline
+            | SprintAreaSymbol::UncrossableMarshWithoutOutline
+            | SprintAreaSymbol::Marsh => SprintPointSymbol::MinimumMarsh,
+            SprintAreaSymbol::Building => SprintPointSymbol::MinimumBuilding,
+            _ => return None,
+        };
+        Some(PointSymbol::Sprint(point))
+    }
+}
+
+impl SymbolTrait for SprintAreaSymbol {
+    // in square meters
+    fn min_size(&self, scale: Scale) -> f64 {
+        // minimum sizes at 1:4_000
+        let min = match self {
+            SprintAreaSymbol::BrokenGround => 7.11,
+            SprintAreaSymbol::GiganticBoulder => 4.76,
+            SprintAreaSymbol::BoulderField => 16.,
+            SprintAreaSymbol::StonyGround => 7.11,
+            SprintAreaSymbol::BareRock => 16.,
+            SprintAreaSymbol::SandyGround => 16.,
+            SprintAreaSymbol::UncrossableWaterWithBankLine => 4.55,
+            SprintAreaSymbol::UncrossableWaterWithoutBankLine => 4.55,
+            SprintAreaSymbol::ShallowWaterWithOutline => 7.84,
+            SprintAreaSymbol::ShallowWaterWithoutOutline => 7.84,
+            SprintAreaSymbol::UncrossableMarshWithOutline => 7.84,
+            SprintAreaSymbol::UncrossableMarshWithoutOutline => 7.84,
+            SprintAreaSymbol::Marsh => 3.2,
+            SprintAreaSymbol::IndistinctMarsh => 22.4,
+            SprintAreaSymbol::OpenLand => 4.55,
+            SprintAreaSymbol::OpenLandScatteredTrees => 64.,
+            SprintAreaSymbol::RoughOpenLand => 16.,
+            SprintAreaSymbol::RoughOpenLandScatteredTrees => 100.,
+            SprintAreaSymbol::LightGreen => 16.,
+            SprintAreaSymbol::UnderGrowth => 24.,
+            SprintAreaSymbol::MediumGreen => 7.84,
+            SprintAreaSymbol::DenseUnderGrowth => 16.,
+            SprintAreaSymbol::DarkGreen => 4.55,
+            SprintAreaSymbol::ImpassableVegetation => 4.55,
+            SprintAreaSymbol::CultivatedLand => 144.,
+            SprintAreaSymbol::Orchard => 64.,
+            SprintAreaSymbol::Vineyard => 64.,
+            SprintAreaSymbol::PavedArea => 16.,
+            SprintAreaSymbol::PavedAreaWithBoundary => 16.,
+            SprintAreaSymbol::PrivateArea => 16.,
+            SprintAreaSymbol::Building => 4.,
+            SprintAreaSymbol::CanopyWithOutline => 5.76,
+            SprintAreaSymbol::CanopyWithoutOutline => 5.76,
+            SprintAreaSymbol::OutOfBounds => 144.,
+            _ => 0.,
+        };
+        min * scale.factor_from(Scale::S4_000).powi(2)
+    }
+
+    fn id(&self) -> u16 {
+        match self {
+            SprintAreaSymbol::BrokenGround => 17,
+            SprintAreaSymbol::GiganticBoulder => 26,
+            SprintAreaSymbol::BoulderField => 28,
+            SprintAreaSymbol::StonyGround => 29,
+            SprintAreaSymbol::BareRock => 30,
+            SprintAreaSymbol::SandyGround => 31,
+            SprintAreaSymbol::UncrossableWaterWithBankLine => 32,
+            SprintAreaSymbol::UncrossableWaterWithoutBankLine => 33,
+            SprintAreaSymbol::ShallowWaterWithOutline => 35,
+            SprintAreaSymbol::ShallowWaterWithoutOutline => 36,
+            SprintAreaSymbol::UncrossableMarshWithOutline => 42,
+            SprintAreaSymbol::UncrossableMarshWithoutOutline => 43,
+            SprintAreaSymbol::Marsh => 44,
+            SprintAreaSymbol::IndistinctMarsh => 47,
+            SprintAreaSymbol::OpenLand => 51,
+            SprintAreaSymbol::OpenLandScatteredTrees => 52,
+            SprintAreaSymbol::RoughOpenLand => 53,
+            SprintAreaSymbol::RoughOpenLandScatteredTrees => 54,
+            SprintAreaSymbol::Forest => 55,
+            SprintAreaSymbol::LightGreen => 56,
+            SprintAreaSymbol::UnderGrowth => 57,
+            SprintAreaSymbol::MediumGreen => 58,
+            SprintAreaSymbol::DenseUnderGrowth => 59,
+            SprintAreaSymbol::DarkGreen => 60,
+            SprintAreaSymbol::ImpassableVegetation => 61,
+            SprintAreaSymbol::CultivatedLand => 62,
+            SprintAreaSymbol::Orchard => 63,
+            SprintAreaSymbol::Vineyard => 64,
+            SprintAreaSymbol::PavedArea => 70,
+            SprintAreaSymbol::PavedAreaWithBoundary => 71,
+            SprintAreaSymbol::PrivateArea => 90,
+            SprintAreaSymbol::Building => 92,
+            SprintAreaSymbol::CanopyWithOutline => 94,
+            SprintAreaSymbol::CanopyWithoutOutline => 95,
+            SprintAreaSymbol::MagneticNorth => 108,
+            SprintAreaSymbol::OutOfBounds => 113,
+        }
+    }
+
+    fn is_rotatable(&self) -> bool {
+        matches!(self, SprintAreaSymbol::Vineyard)
+    }
+}
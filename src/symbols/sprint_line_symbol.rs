@@ -0,0 +1,211 @@
+use super::{PointSymbol, SprintPointSymbol, SymbolTrait};
+use crate::Scale;
+
+/// ISSprOM 2019-2 symbols for line objects
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SprintLineSymbol {
+    Contour,
+    BasemapContour,
+    NegBasemapContour,
+    IndexContour,
+    FormLine,
+    EarthBank,
+    EarthWall,
+    ErosionGully,
+    SmallErosionGully,
+    ImpassableCliff,
+    Cliff,
+    BankLine,
+    ShallowWaterOutline,
+    CrossableWatercourse,
+    SmallCrossableWatercourse,
+    SeasonalWatercourse,
+    NarrowMarsh,
+    DistinctCultivationBoundary,
+    DistinctVegetationBoundary,
+    StepOrEdgeOfPavedArea,
+    WideRoad,
+    Road,
+    VehicleTrack,
+    Footpath,
+    SmallFootpath,
+    IndistinctFootpath,
+    Railway,
+    PowerLine,
+    MajorPowerLine,
+    BridgeTunnel,
+    PassableWall,
+    ImpassableWall,
+    PassableFence,
+    ImpassableFence,
+    PrivateAreaBoundingLine,
+    CanopyOutline,
+    Ruin,
+    ProminentLinearFeature,
+    ImpassableProminentLinearFeature,
+    Stairway,
+    SimpleOrienteeringCourse,
+}
+
+impl SprintLineSymbol {
+    /// Get the symbol with the given id in the symbols_4.txt file
+    pub(crate) fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(SprintLineSymbol::Contour),
+            2 => Some(SprintLineSymbol::BasemapContour),
+            3 => Some(SprintLineSymbol::NegBasemapContour),
+            4 => Some(SprintLineSymbol::IndexContour),
+            6 => Some(SprintLineSymbol::FormLine),
+            8 => Some(SprintLineSymbol::EarthBank),
+            10 => Some(SprintLineSymbol::EarthWall),
+            11 => Some(SprintLineSymbol::ErosionGully),
+            12 => Some(SprintLineSymbol::SmallErosionGully),
+            19 => Some(SprintLineSymbol::ImpassableCliff),
+            21 => Some(SprintLineSymbol::Cliff),
+            34 => Some(SprintLineSymbol::BankLine),
+            37 => Some(SprintLineSymbol::ShallowWaterOutline),
+            39 => Some(SprintLineSymbol::CrossableWatercourse),
+            40 => Some(SprintLineSymbol::SmallCrossableWatercourse),
+            41 => Some(SprintLineSymbol::SeasonalWatercourse),
+            46 => Some(SprintLineSymbol::NarrowMarsh),
+            65 => Some(SprintLineSymbol::DistinctCultivationBoundary),
+            66 => Some(SprintLineSymbol::DistinctVegetationBoundary),
+            72 => Some(SprintLineSymbol::StepOrEdgeOfPavedArea),
+            73 => Some(SprintLineSymbol::WideRoad),
+            74 => Some(SprintLineSymbol::Road),
+            75 => Some(SprintLineSymbol::VehicleTrack),
+            76 => Some(SprintLineSymbol::Footpath),
+            77 => Some(SprintLineSymbol::SmallFootpath),
+            78 => Some(SprintLineSymbol::IndistinctFootpath),
+            79 => Some(SprintLineSymbol::Railway),
+            80 => Some(SprintLineSymbol::PowerLine),
+            81 => Some(SprintLineSymbol::MajorPowerLine),
+            82 => Some(SprintLineSymbol::BridgeTunnel),
+            85 => Some(SprintLineSymbol::PassableWall),
+            86 => Some(SprintLineSymbol::ImpassableWall),
+            87 => Some(SprintLineSymbol::PassableFence),
+            88 => Some(SprintLineSymbol::ImpassableFence),
+            91 => Some(SprintLineSymbol::PrivateAreaBoundingLine),
+            96 => Some(SprintLineSymbol::CanopyOutline),
+            97 => Some(SprintLineSymbol::Ruin),
+            103 => Some(SprintLineSymbol::ProminentLinearFeature),
+            104 => Some(SprintLineSymbol::ImpassableProminentLinearFeature),
+            107 => Some(SprintLineSymbol::Stairway),
+            114 => Some(SprintLineSymbol::SimpleOrienteeringCourse),
+            _ => None,
+        }
+    }
+
+    /// Check if the symbol is a contour, index contour, form line or basemap contour
+    pub fn is_contour(&self) -> bool {
+        matches!(
+            self,
+            SprintLineSymbol::Contour
+                | SprintLineSymbol::BasemapContour
+                | SprintLineSymbol::NegBasemapContour
+                | SprintLineSymbol::IndexContour
+                | SprintLineSymbol::FormLine
+        )
+    }
+
+    /// The point symbol an object shorter than the minimum length of the symbol is drawn with, if there is one
+    pub fn min_size_point_symbol(&self) -> Option<PointSymbol> {
+        let point = match self {
+            SprintLineSymbol::EarthBank => SprintPointSymbol::MinimumEarthBank,
+            SprintLineSymbol::ImpassableCliff => SprintPointSymbol::MinimumImpassableCliff,
+            SprintLineSymbol::Cliff => SprintPointSymbol::MinimumCliff,
+            SprintLineSymbol::NarrowMarsh => SprintPointSymbol::MinimumMarsh,
+            SprintLineSymbol::BridgeTunnel => SprintPointSymbol::MinimumBridgeTunnel,
+            SprintLineSymbol::Ruin => SprintPointSymbol::MinimumRuin,
+            _ => return None,
+        };
+        Some(PointSymbol::Sprint(point))
+    }
+}
+
+impl SymbolTrait for SprintLineSymbol {
+    // in meters
+    fn min_size(&self, scale: Scale) -> f64 {
+        // minimum sizes at 1:4_000
+        let min = match self {
+            SprintLineSymbol::FormLine => 4.4,
+            SprintLineSymbol::EarthBank => 2.4,
+            SprintLineSymbol::EarthWall => 5.6,
+            SprintLineSymbol::ErosionGully => 4.6,
+            SprintLineSymbol::SmallErosionGully => 2.8,
+            SprintLineSymbol::ImpassableCliff => 2.4,
+            SprintLineSymbol::Cliff => 2.4,
+            SprintLineSymbol::CrossableWatercourse => 4.,
+            SprintLineSymbol::SmallCrossableWatercourse => 4.,
+            SprintLineSymbol::SeasonalWatercourse => 10.93,
+            SprintLineSymbol::NarrowMarsh => 2.8,
+            SprintLineSymbol::DistinctCultivationBoundary => 8.,
+            SprintLineSymbol::DistinctVegetationBoundary => 7.2,
+            SprintLineSymbol::VehicleTrack => 25.07,
+            SprintLineSymbol::Footpath => 17.07,
+            SprintLineSymbol::SmallFootpath => 9.07,
+            SprintLineSymbol::IndistinctFootpath => 21.2,
+            SprintLineSymbol::Railway => 16.,
+            SprintLineSymbol::PowerLine => 20.,
+            SprintLineSymbol::MajorPowerLine => 20.,
+            SprintLineSymbol::BridgeTunnel => 1.6,
+            SprintLineSymbol::PassableWall => 5.6,
+            SprintLineSymbol::ImpassableWall => 12.,
+            SprintLineSymbol::PassableFence => 6.,
+            SprintLineSymbol::ImpassableFence => 8.,
+            SprintLineSymbol::Ruin => 12.8,
+            SprintLineSymbol::ProminentLinearFeature => 6.,
+            SprintLineSymbol::ImpassableProminentLinearFeature => 8.,
+            SprintLineSymbol::Stairway => 6.4,
+            _ => 0.,
+        };
+        min * scale.factor_from(Scale::S4_000)
+    }
+
+    fn id(&self) -> u16 {
+        match self {
+            SprintLineSymbol::Contour => 0,
+            SprintLineSymbol::BasemapContour => 2,
+            SprintLineSymbol::NegBasemapContour => 3,
+            SprintLineSymbol::IndexContour => 4,
+            SprintLineSymbol::FormLine => 6,
+            SprintLineSymbol::EarthBank => 8,
+            SprintLineSymbol::EarthWall => 10,
+            SprintLineSymbol::ErosionGully => 11,
+            SprintLineSymbol::SmallErosionGully => 12,
+            SprintLineSymbol::ImpassableCliff => 19,
+            SprintLineSymbol::Cliff => 21,
+            SprintLineSymbol::BankLine => 34,
+            SprintLineSymbol::ShallowWaterOutline => 37,
+            SprintLineSymbol::CrossableWatercourse => 39,
+            SprintLineSymbol::SmallCrossableWatercourse => 40,
+            SprintLineSymbol::SeasonalWatercourse => 41,
+            SprintLineSymbol::NarrowMarsh => 46,
+            SprintLineSymbol::DistinctCultivationBoundary => 65,
+            SprintLineSymbol::DistinctVegetationBoundary => 66,
+            SprintLineSymbol::StepOrEdgeOfPavedArea => 72,
+            SprintLineSymbol::WideRoad => 73,
+            SprintLineSymbol::Road => 74,
+            SprintLineSymbol::VehicleTrack => 75,
+            SprintLineSymbol::Footpath => 76,
+            SprintLineSymbol::SmallFootpath => 77,
+            SprintLineSymbol::IndistinctFootpath => 78,
+            SprintLineSymbol::Railway => 79,
+            SprintLineSymbol::PowerLine => 80,
+            SprintLineSymbol::MajorPowerLine => 81,
+            SprintLineSymbol::BridgeTunnel => 82,
+            SprintLineSymbol::PassableWall => 85,
+            SprintLineSymbol::ImpassableWall => 86,
+            SprintLineSymbol::PassableFence => 87,
+            SprintLineSymbol::ImpassableFence => 88,
+            SprintLineSymbol::PrivateAreaBoundingLine => 91,
+            SprintLineSymbol::CanopyOutline => 96,
+            SprintLineSymbol::Ruin => 97,
+            SprintLineSymbol::ProminentLinearFeature => 103,
+            SprintLineSymbol::ImpassableProminentLinearFeature => 104,
+            SprintLineSymbol::Stairway => 107,
+            SprintLineSymbol::SimpleOrienteeringCourse => 114,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use super::SymbolTrait;
+
+/// ISSprOM 2019-2 symbols for point objects
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SprintPointSymbol {
+    SlopeLineContour,
+    SlopeLineFormLine,
+    MinimumEarthBank,
+    DotKnoll,
+    ElongatedDotKnoll,
+    UDepression,
+    Pit,
+    ProminentLandFeature,
+    MinimumImpassableCliff,
+    MinimumCliff,
+    RockyPitCave,
+    SmallBoulder,
+    LargeBoulder,
+    BoulderCluster,
+    WaterFilledPit,
+    MinimumMarsh,
+    Well,
+    Spring,
+    ProminentWaterFeature,
+    ProminentTree,
+    ProminentBush,
+    ProminentVegetationFeature,
+    MinimumBridgeTunnel,
+    Footbridge,
+    CrossingPoint,
+    MinimumBuilding,
+    MinimumRuin,
+    HighTower,
+    Tower,
+    Cairn,
+    FodderRack,
+    ProminentManMadeFeatureO,
+    ProminentManMadeFeatureX,
+    RegistrationMark,
+    SpotHeight,
+}
+
+impl SprintPointSymbol {
+    /// Get the symbol with the given id in the symbols_4.txt file
+    pub(crate) fn from_id(id: u16) -> Option<Self> {
+        match id {
+            1 => Some(SprintPointSymbol::SlopeLineContour),
+            7 => Some(SprintPointSymbol::SlopeLineFormLine),
+            9 => Some(SprintPointSymbol::MinimumEarthBank),
+            13 => Some(SprintPointSymbol::DotKnoll),
+            14 => Some(SprintPointSymbol::ElongatedDotKnoll),
+            15 => Some(SprintPointSymbol::UDepression),
+            16 => Some(SprintPointSymbol::Pit),
+            18 => Some(SprintPointSymbol::ProminentLandFeature),
+            20 => Some(SprintPointSymbol::MinimumImpassableCliff),
+            22 => Some(SprintPointSymbol::MinimumCliff),
+            23 => Some(SprintPointSymbol::RockyPitCave),
+            24 => Some(SprintPointSymbol::SmallBoulder),
+            25 => Some(SprintPointSymbol::LargeBoulder),
+            27 => Some(SprintPointSymbol::BoulderCluster),
+            38 => Some(SprintPointSymbol::WaterFilledPit),
+            45 => Some(SprintPointSymbol::MinimumMarsh),
+            48 => Some(SprintPointSymbol::Well),
+            49 => Some(SprintPointSymbol::Spring),
+            50 => Some(SprintPointSymbol::ProminentWaterFeature),
+            67 => Some(SprintPointSymbol::ProminentTree),
+            68 => Some(SprintPointSymbol::ProminentBush),
+            69 => Some(SprintPointSymbol::ProminentVegetationFeature),
+            83 => Some(SprintPointSymbol::MinimumBridgeTunnel),
+            84 => Some(SprintPointSymbol::Footbridge),
+            89 => Some(SprintPointSymbol::CrossingPoint),
+            93 => Some(SprintPointSymbol::MinimumBuilding),
+            98 => Some(SprintPointSymbol::MinimumRuin),
+            99 => Some(SprintPointSymbol::HighTower),
+            100 => Some(SprintPointSymbol::Tower),
+            101 => Some(SprintPointSymbol::Cairn),
+            102 => Some(SprintPointSymbol::FodderRack),
+            105 => Some(SprintPointSymbol::ProminentManMadeFeatureO),
+            106 => Some(SprintPointSymbol::ProminentManMadeFeatureX),
+            109 => Some(SprintPointSymbol::RegistrationMark),
+            110 => Some(SprintPointSymbol::SpotHeight),
+            _ => None,
+        }
+    }
+}
+
+impl SymbolTrait for SprintPointSymbol {
+    fn id(&self) -> u16 {
+        match self {
+            SprintPointSymbol::SlopeLineContour => 1,
+            SprintPointSymbol::SlopeLineFormLine => 7,
+            SprintPointSymbol::MinimumEarthBank => 9,
+            SprintPointSymbol::DotKnoll => 13,
+            SprintPointSymbol::ElongatedDotKnoll => 14,
+            SprintPointSymbol::UDepression => 15,
+            SprintPointSymbol::Pit => 16,
+            SprintPointSymbol::ProminentLandFeature => 18,
+            SprintPointSymbol::MinimumImpassableCliff => 20,
+            SprintPointSymbol::MinimumCliff => 22,
+            SprintPointSymbol::RockyPitCave => 23,
+            SprintPointSymbol::SmallBoulder => 24,
+            SprintPointSymbol::LargeBoulder => 25,
+            SprintPointSymbol::BoulderCluster => 27,
+            SprintPointSymbol::WaterFilledPit => 38,
+            SprintPointSymbol::MinimumMarsh => 45,
+            SprintPointSymbol::Well => 48,
+            SprintPointSymbol::Spring => 49,
+            SprintPointSymbol::ProminentWaterFeature => 50,
+            SprintPointSymbol::ProminentTree => 67,
+            SprintPointSymbol::ProminentBush => 68,
+            SprintPointSymbol::ProminentVegetationFeature => 69,
+            SprintPointSymbol::MinimumBridgeTunnel => 83,
+            SprintPointSymbol::Footbridge => 84,
+            SprintPointSymbol::CrossingPoint => 89,
+            SprintPointSymbol::MinimumBuilding => 93,
+            SprintPointSymbol::MinimumRuin => 98,
+            SprintPointSymbol::HighTower => 99,
+            SprintPointSymbol::Tower => 100,
+            SprintPointSymbol::Cairn => 101,
+            SprintPointSymbol::FodderRack => 102,
+            SprintPointSymbol::ProminentManMadeFeatureO => 105,
+            SprintPointSymbol::ProminentManMadeFeatureX => 106,
+            SprintPointSymbol::RegistrationMark => 109,
+            SprintPointSymbol::SpotHeight => 110,
+        }
+    }
+
+    fn is_rotatable(&self) -> bool {
+        matches!(
+            self,
+            SprintPointSymbol::SlopeLineContour
+                | SprintPointSymbol::SlopeLineFormLine
+                | SprintPointSymbol::MinimumEarthBank
+                | SprintPointSymbol::ElongatedDotKnoll
+                | SprintPointSymbol::MinimumImpassableCliff
+                | SprintPointSymbol::MinimumCliff
+                | SprintPointSymbol::RockyPitCave
+                | SprintPointSymbol::Spring
+                | SprintPointSymbol::MinimumBridgeTunnel
+                | SprintPointSymbol::Footbridge
+                | SprintPointSymbol::CrossingPoint
+                | SprintPointSymbol::MinimumBuilding
+                | SprintPointSymbol::MinimumRuin
+        )
+    }
+}
@@ -0,0 +1,32 @@
+use super::SymbolTrait;
+
+/// ISSprOM 2019-2 symbols for text objects
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SprintTextSymbol {
+    ContourValue,
+    SpotHeight,
+    ControlNumber,
+}
+
+impl SprintTextSymbol {
+    /// Get the symbol with the given id in the symbols_4.txt file
+    pub(crate) fn from_id(id: u16) -> Option<Self> {
+        match id {
+            5 => Some(SprintTextSymbol::ContourValue),
+            111 => Some(SprintTextSymbol::SpotHeight),
+            112 => Some(SprintTextSymbol::ControlNumber),
+            _ => None,
+        }
+    }
+}
+
+impl SymbolTrait for SprintTextSymbol {
+    fn id(&self) -> u16 {
+        match self {
+            SprintTextSymbol::ContourValue => 5,
+            SprintTextSymbol::SpotHeight => 111,
+            SprintTextSymbol::ControlNumber => 112,
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use super::{AreaSymbol, LineSymbol, PointSymbol, SymbolTrait, TextSymbol};
+use super::{
+    AreaSymbol, LineSymbol, PointSymbol, SprintAreaSymbol, SprintLineSymbol, SprintPointSymbol,
+    SprintTextSymbol, SymbolTrait, TextSymbol,
+};
 use crate::Scale;
 use std::fmt;
 
@@ -35,6 +38,28 @@ impl Symbol {
     pub fn is_text_symbol(&self) -> bool {
         matches!(self, Symbol::Text(_))
     }
+
+    /// Check if symbol is an ISSprOM 2019-2 symbol
+    pub fn is_sprint_symbol(&self) -> bool {
+        matches!(
+            self,
+            Symbol::Area(AreaSymbol::Sprint(_))
+                | Symbol::Line(LineSymbol::Sprint(_))
+                | Symbol::Point(PointSymbol::Sprint(_))
+                | Symbol::Text(TextSymbol::Sprint(_))
+        )
+    }
+
+    /// Check if symbol is a symbol of a custom symbol set
+    pub fn is_custom_symbol(&self) -> bool {
+        matches!(
+            self,
+            Symbol::Area(AreaSymbol::Custom(_))
+                | Symbol::Line(LineSymbol::Custom(_))
+                | Symbol::Point(PointSymbol::Custom(_))
+                | Symbol::Text(TextSymbol::Custom(_))
+        )
+    }
 }
 
 impl fmt::Display for Symbol {
@@ -72,6 +97,74 @@ impl From<TextSymbol> for Symbol {
     }
 }
 
+impl From<SprintAreaSymbol> for Symbol {
+    fn from(value: SprintAreaSymbol) -> Self {
+        Symbol::Area(AreaSymbol::Sprint(value))
+    }
+}
+
+impl From<SprintLineSymbol> for Symbol {
+    fn from(value: SprintLineSymbol) -> Self {
+        Symbol::Line(LineSymbol::Sprint(value))
+    }
+}
+
+impl From<SprintPointSymbol> for Symbol {
+    fn from(value: SprintPointSymbol) -> Self {
+        Symbol::Point(PointSymbol::Sprint(value))
+    }
+}
+
+impl From<SprintTextSymbol> for Symbol {
+    fn from(value: SprintTextSymbol) -> Self {
+        Symbol::Text(TextSymbol::Sprint(value))
+    }
+}
+
+impl TryFrom<Symbol> for AreaSymbol {
+    type Error = Symbol;
+
+    fn try_from(value: Symbol) -> Result<Self, Self::Error> {
+        match value {
+            Symbol::Area(a) => Ok(a),
+            _ => Err(value),
+        }
+    }
+}
+
+impl TryFrom<Symbol> for LineSymbol {
+    type Error = Symbol;
+
+    fn try_from(value: Symbol) -> Result<Self, Self::Error> {
+        match value {
+            Symbol::Line(l) => Ok(l),
+            _ => Err(value),
+        }
+    }
+}
+
+impl TryFrom<Symbol> for PointSymbol {
+    type Error = Symbol;
+
+    fn try_from(value: Symbol) -> Result<Self, Self::Error> {
+        match value {
+            Symbol::Point(p) => Ok(p),
+            _ => Err(value),
+        }
+    }
+}
+
+impl TryFrom<Symbol> for TextSymbol {
+    type Error = Symbol;
+
+    fn try_from(value: Symbol) -> Result<Self, Self::Error> {
+        match value {
+            Symbol::Text(t) => Ok(t),
+            _ => Err(value),
+        }
+    }
+}
+
 impl SymbolTrait for Symbol {
     fn min_size(&self, scale: Scale) -> f64 {
         match self {
@@ -82,7 +175,7 @@ impl SymbolTrait for Symbol {
         }
     }
 
-    fn id(&self) -> u8 {
+    fn id(&self) -> u16 {
         match self {
             Symbol::Area(a) => a.id(),
             Symbol::Line(l) => l.id(),
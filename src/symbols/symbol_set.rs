@@ -0,0 +1,184 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, OnceLock},
+};
+
+use crate::OmapResult;
+
+use super::{
+    AreaSymbol, CustomSymbolSet, LineSymbol, PointSymbol, SprintAreaSymbol, SprintLineSymbol,
+    SprintPointSymbol, SprintTextSymbol, Symbol, SymbolTrait, TextSymbol,
+};
+
+/// The symbol sets that can be written to a map
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SymbolSet {
+    /// ISOM 2017-2 with the symbol dimensions of 1:15_000
+    Isom2017_2,
+    /// ISOM 2017-2 with the symbol dimensions enlarged to 150%, as used for 1:10_000
+    Isom2017_2Enlarged,
+    /// ISSprOM 2019-2 for sprint maps at 1:4_000  
+    /// The symbol definitions are adapted from the 1:15_000 ISOM 2017-2 set with the ISSprOM codes and names,
+    /// and the impassable vegetation and buildings built as ISSprOM draws them
+    Issprom2019_2,
+    /// A symbol set with its color table loaded at runtime
+    Custom(Arc<CustomSymbolSet>),
+}
+
+impl From<CustomSymbolSet> for SymbolSet {
+    fn from(value: CustomSymbolSet) -> Self {
+        SymbolSet::Custom(Arc::new(value))
+    }
+}
+
+impl SymbolSet {
+    /// Check if a symbol is part of the symbol set  
+    /// The ISOM sets contain the symbols of the ISOM enums, the ISSprOM set the `Sprint` symbols
+    /// and a custom set the `Custom` symbols it defines
+    pub fn contains(&self, symbol: impl Into<Symbol>) -> bool {
+        let symbol = symbol.into();
+        match self {
+            SymbolSet::Isom2017_2 | SymbolSet::Isom2017_2Enlarged => {
+                !symbol.is_sprint_symbol() && !symbol.is_custom_symbol()
+            }
+            SymbolSet::Issprom2019_2 => symbol.is_sprint_symbol(),
+            SymbolSet::Custom(custom) => custom.contains(symbol),
+        }
+    }
+
+    /// The id of the symbol set as written to the map file
+    pub fn name(&self) -> &str {
+        match self {
+            SymbolSet::Isom2017_2 | SymbolSet::Isom2017_2Enlarged => "ISOM 2017-2",
+            SymbolSet::Issprom2019_2 => "ISSprOM 2019-2",
+            SymbolSet::Custom(custom) => custom.name(),
+        }
+    }
+
+    /// Get the symbol with the given id in the symbol set
+    pub(crate) fn symbol(&self, id: u16) -> Option<Symbol> {
+        match self {
+            SymbolSet::Isom2017_2 | SymbolSet::Isom2017_2Enlarged => AreaSymbol::from_id(id)
+                .map(Symbol::Area)
+                .or_else(|| LineSymbol::from_id(id).map(Symbol::Line))
+                .or_else(|| PointSymbol::from_id(id).map(Symbol::Point))
+                .or_else(|| TextSymbol::from_id(id).map(Symbol::Text)),
+            SymbolSet::Issprom2019_2 => SprintAreaSymbol::from_id(id)
+                .map(Symbol::from)
+                .or_else(|| SprintLineSymbol::from_id(id).map(Symbol::from))
+                .or_else(|| SprintPointSymbol::from_id(id).map(Symbol::from))
+                .or_else(|| SprintTextSymbol::from_id(id).map(Symbol::from)),
+            SymbolSet::Custom(custom) => custom.symbol_from_id(id),
+        }
+    }
+
+    /// Get the symbol set of a file from its content, see [Self::built_in_like]
+    pub(crate) fn from_xml(content: &str) -> OmapResult<SymbolSet> {
+        let custom = CustomSymbolSet::from_xml(content)?;
+        Ok(Self::built_in_like(&custom).unwrap_or_else(|| custom.into()))
+    }
+
+    /// The built-in set with the name and the symbol ids, types and codes of a set loaded from a file  
+    /// Colors and symbols edited in Mapper keep the set recognized, while sets with added or removed symbols are not.
+    /// The ISOM sets share their symbols, so the one with the most lines in common with the file is taken
+    pub(crate) fn built_in_like(custom: &CustomSymbolSet) -> Option<SymbolSet> {
+        [
+            SymbolSet::Isom2017_2,
+            SymbolSet::Isom2017_2Enlarged,
+            SymbolSet::Issprom2019_2,
+        ]
+        .into_iter()
+        .filter(|s| s.name() == custom.name())
+        .filter(|s| s.parsed().is_some_and(|p| p.same_symbols(custom)))
+        .max_by_key(|s| shared_lines(s.symbols(), custom.symbols_xml()))
+    }
+
+    /// The priority of the top-most color of a symbol in the set, lower is drawn on top  
+    /// Symbols without colors or not in the set get the lowest priority
+    pub(crate) fn drawing_priority(&self, symbol: Symbol) -> u16 {
+        let index = match self {
+            SymbolSet::Custom(custom) => Some(custom.as_ref()),
+            _ => self.parsed(),
+        };
+        index
+            .and_then(|i| i.priority_from_id(symbol.id()))
+            .unwrap_or(u16::MAX)
+    }
+
+    // the embedded colors and symbols of a built-in set, parsed once
+    fn parsed(&self) -> Option<&'static CustomSymbolSet> {
+        static ISOM_15: OnceLock<Option<CustomSymbolSet>> = OnceLock::new();
+        static ISOM_10: OnceLock<Option<CustomSymbolSet>> = OnceLock::new();
+        static ISSPROM: OnceLock<Option<CustomSymbolSet>> = OnceLock::new();
+
+        let parse = || CustomSymbolSet::from_xml(&[self.colors(), self.symbols()].concat()).ok();
+        match self {
+            SymbolSet::Isom2017_2 => ISOM_15.get_or_init(parse).as_ref(),
+            SymbolSet::Isom2017_2Enlarged => ISOM_10.get_or_init(parse).as_ref(),
+            SymbolSet::Issprom2019_2 => ISSPROM.get_or_init(parse).as_ref(),
+            SymbolSet::Custom(_) => None,
+        }
+    }
+
+    pub(crate) fn colors(&self) -> &str {
+        match self {
+            SymbolSet::Custom(custom) => custom.colors_xml(),
+            // the writer adds the line break after the colors
+            _ => include_str!("../colors.txt").trim_end(),
+        }
+    }
+
+    pub(crate) fn symbols(&self) -> &str {
+        match self {
+            SymbolSet::Isom2017_2 => include_str!("../symbols_15.txt"),
+            SymbolSet::Isom2017_2Enlarged => include_str!("../symbols_10.txt"),
+            SymbolSet::Issprom2019_2 => include_str!("../symbols_4.txt"),
+            SymbolSet::Custom(custom) => custom.symbols_xml(),
+        }
+    }
+}
+
+// the number of lines of the xml snippet `b` also found in `a`, ignoring the indentation
+fn shared_lines(a: &str, b: &str) -> usize {
+    let lines: HashSet<&str> = a.lines().map(str::trim).collect();
+    b.lines().filter(|l| lines.contains(l.trim())).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SymbolSet;
+    use crate::symbols::{Symbol, SymbolTrait};
+
+    fn same_type(a: Symbol, b: Symbol) -> bool {
+        std::mem::discriminant(&a) == std::mem::discriminant(&b)
+    }
+
+    #[test]
+    fn enum_symbols_are_defined_in_the_set_files() {
+        for set in [
+            SymbolSet::Isom2017_2,
+            SymbolSet::Isom2017_2Enlarged,
+            SymbolSet::Issprom2019_2,
+        ] {
+            let parsed = set.parsed().expect("the built-in set is parsed");
+            let mut mapped = 0;
+            for id in 0..=u16::MAX {
+                let Some(symbol) = set.symbol(id) else {
+                    continue;
+                };
+                mapped += 1;
+                assert_eq!(symbol.id(), id, "{}", set.name());
+                assert_eq!(symbol.is_sprint_symbol(), set == SymbolSet::Issprom2019_2);
+                let defined = parsed
+                    .symbol_from_id(id)
+                    .unwrap_or_else(|| panic!("id {id} is missing in {}", set.name()));
+                assert!(
+                    same_type(symbol, defined),
+                    "{symbol:?} is a {defined:?} in {}",
+                    set.name()
+                );
+            }
+            assert!(mapped > 100, "{}", set.name());
+        }
+    }
+}
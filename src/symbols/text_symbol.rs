@@ -1,4 +1,4 @@
-use super::SymbolTrait;
+use super::{SprintTextSymbol, SymbolTrait};
 
 /// Symbols for text objects
 #[allow(missing_docs)]
@@ -7,14 +7,38 @@ pub enum TextSymbol {
     ContourValue,
     SpotHeight,
     ControlNumber,
+    /// ISSprOM 2019-2 text symbols
+    Sprint(SprintTextSymbol),
+    /// A symbol of a [CustomSymbolSet](super::CustomSymbolSet) by its id in the set
+    Custom(u16),
+}
+
+impl TextSymbol {
+    /// Get the symbol with the given id in the symbol_x.txt files
+    pub(crate) fn from_id(id: u16) -> Option<Self> {
+        match id {
+            5 => Some(TextSymbol::ContourValue),
+            164 => Some(TextSymbol::SpotHeight),
+            165 => Some(TextSymbol::ControlNumber),
+            _ => None,
+        }
+    }
 }
 
 impl SymbolTrait for TextSymbol {
-    fn id(&self) -> u8 {
+    fn id(&self) -> u16 {
         match self {
+            TextSymbol::Sprint(s) => s.id(),
+            TextSymbol::Custom(id) => *id,
             TextSymbol::ContourValue => 5,
             TextSymbol::SpotHeight => 164,
             TextSymbol::ControlNumber => 165,
         }
     }
 }
+
+impl From<SprintTextSymbol> for TextSymbol {
+    fn from(value: SprintTextSymbol) -> Self {
+        TextSymbol::Sprint(value)
+    }
+}
@@ -0,0 +1,345 @@
+use geo_types::Coord;
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use std::{collections::HashMap, fs::File, io::BufWriter, ops::Range, path::Path};
+
+use crate::{
+    coordinate_mode::ToRelative,
+    deserialize::{attribute, read_georeferencing, Georeferencing},
+    map_part::MapPart,
+    objects::MapObject,
+    symbols::{Symbol, SymbolSet, SymbolTrait},
+    xml_writer::XmlWriter,
+    Crs, ObjectOrder, OmapError, OmapResult,
+};
+
+/// Selection of the objects in an existing map that are replaced when updating it with [crate::Omap::update_file]
+#[derive(Debug, Clone)]
+pub enum ReplaceFilter {
+    /// Replace all objects with any of the symbols
+    Symbols(Vec<Symbol>),
+    /// Replace all objects carrying a tag with the key and value
+    Tag(String, String),
+}
+
+impl ReplaceFilter {
+    fn matches(&self, symbol_id: Option<i32>, tags: &HashMap<String, String>) -> bool {
+        match self {
+            ReplaceFilter::Symbols(symbols) => {
+                symbol_id.is_some_and(|id| symbols.iter().any(|s| s.id() as i32 == id))
+            }
+            ReplaceFilter::Tag(key, value) => tags.get(key).is_some_and(|v| v == value),
+        }
+    }
+}
+
+// an <objects> element of a part
+struct ObjectsElement {
+    // the name of the part the element belongs to
+    part_name: String,
+    // the byte range of the start tag, or the whole element if it is empty
+    start_tag: Range<usize>,
+    is_empty: bool,
+    // the byte position of the </objects> end tag
+    end_tag: usize,
+    kept_objects: usize,
+}
+
+struct MapScan {
+    georef: Georeferencing,
+    symbol_set: SymbolSet,
+    removed_objects: Vec<Range<usize>>,
+    objects_elements: Vec<ObjectsElement>,
+    // the byte range of the <parts> start tag
+    parts_start_tag: Range<usize>,
+    // the byte position of the </parts> end tag
+    parts_end_tag: usize,
+    current_part: usize,
+}
+
+/// Replace the objects matched by `filter` in the omap file at `path` with the objects of `parts`
+/// Everything else in the file is left untouched, the objects of each part are added to the part of the map with the same name
+/// and parts not in the map are added to it.
+/// All new objects must have symbols in the symbol set of the file
+pub(crate) fn update_file(
+    path: &Path,
+    filter: &ReplaceFilter,
+    parts: &[MapPart],
+    object_order: &ObjectOrder,
+    to_relative: &ToRelative,
+    crs: Option<&Crs>,
+    bezier_error: Option<f64>,
+) -> OmapResult<()> {
+    let content = std::fs::read_to_string(path)?;
+    let scan = scan_map(&content, filter)?;
+
+    if let (Some(crs), Some(file_crs)) = (crs, &scan.georef.crs) {
+        if crs != file_crs {
+            return Err(OmapError::MismatchedCrs(crs.clone(), file_crs.clone()));
+        }
+    }
+    for symbol in parts.iter().flat_map(|p| p.objects.keys()) {
+        if !scan.symbol_set.contains(*symbol) {
+            return Err(OmapError::SymbolNotInSymbolSet(
+                *symbol,
+                scan.symbol_set.name().to_string(),
+            ));
+        }
+    }
+    // the new objects of each <objects> element of the file, and the parts that are not in the file
+    let mut new_objects = vec![Vec::new(); scan.objects_elements.len()];
+    let mut new_parts = Vec::new();
+    for part in parts {
+        let objects = object_order.apply(&part.objects, &scan.symbol_set);
+        match scan
+            .objects_elements
+            .iter()
+            .position(|e| e.part_name == part.name)
+        {
+            Some(i) => new_objects[i].extend(objects),
+            None if !objects.is_empty() => new_parts.push((part.name.as_str(), objects)),
+            None => (),
+        }
+    }
+
+    // the new objects are relative our ref point, but must be written relative the ref point of the file
+    let offset =
+        to_relative.ref_point() - scan.georef.ref_point - scan.georef.to_projected(Coord::zero());
+    let inv_combined_scale_factor = 1. / scan.georef.combined_scale_factor;
+
+    let tmp_path = path.with_extension("omap.tmp");
+    let mut f = XmlWriter::new(BufWriter::new(File::create(&tmp_path)?));
+
+    let write_objects = |f: &mut XmlWriter<_>, objects: &[&MapObject]| -> OmapResult<()> {
+        for mut obj in objects.iter().map(|&o| o.clone()) {
+            to_relative.apply(&mut obj)?;
+            obj.translate(offset);
+            obj.write_to_map(
+                f,
+                bezier_error,
+                scan.georef.scale,
+                scan.georef.grivation,
+                inv_combined_scale_factor,
+            )?;
+        }
+        Ok(())
+    };
+
+    let bytes = content.as_bytes();
+    let mut pos = 0;
+    if !new_parts.is_empty() {
+        f.raw(&bytes[..scan.parts_start_tag.start])?;
+        f.start(
+            "parts",
+            &[
+                ("count", &(scan.objects_elements.len() + new_parts.len())),
+                ("current", &scan.current_part),
+            ],
+        )?;
+        pos = scan.parts_start_tag.end;
+    }
+    let mut removed = scan.removed_objects.iter().peekable();
+    for (element, objects) in scan.objects_elements.iter().zip(&new_objects) {
+        while let Some(r) = removed.next_if(|r| r.start < element.start_tag.start) {
+            f.raw(&bytes[pos..r.start])?;
+            pos = r.end;
+        }
+        f.raw(&bytes[pos..element.start_tag.start])?;
+
+        let count = element.kept_objects + objects.len();
+        if element.is_empty && count > 0 {
+            f.start("objects", &[("count", &count)])?;
+            f.newline()?;
+        } else if element.is_empty {
+            f.empty("objects", &[("count", &0)])?;
+        } else {
+            f.start("objects", &[("count", &count)])?;
+        }
+        pos = element.start_tag.end;
+
+        if !element.is_empty {
+            while let Some(r) = removed.next_if(|r| r.start < element.end_tag) {
+                f.raw(&bytes[pos..r.start])?;
+                pos = r.end;
+            }
+            f.raw(&bytes[pos..element.end_tag])?;
+            pos = element.end_tag;
+        }
+
+        write_objects(&mut f, objects)?;
+        if element.is_empty && count > 0 {
+            f.end("objects")?;
+        }
+    }
+    if !new_parts.is_empty() {
+        f.raw(&bytes[pos..scan.parts_end_tag])?;
+        pos = scan.parts_end_tag;
+        for (name, objects) in new_parts {
+            f.start("part", &[("name", &name)])?;
+            f.start("objects", &[("count", &objects.len())])?;
+            f.newline()?;
+            write_objects(&mut f, &objects)?;
+            f.end("objects")?;
+            f.end("part")?;
+            f.newline()?;
+        }
+    }
+    f.raw(&bytes[pos..])?;
+    f.flush()?;
+    drop(f);
+
+    std::fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+fn scan_map(content: &str, filter: &ReplaceFilter) -> OmapResult<MapScan> {
+    let mut reader = Reader::from_str(content);
+
+    let mut georef = None;
+    let mut removed_objects = Vec::new();
+    let mut objects_elements = Vec::new();
+    let mut parts_start_tag = None;
+    let mut parts_end_tag = None;
+    let mut part_name = String::new();
+    let mut current_part = 0;
+
+    let mut buf = Vec::new();
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"georeferencing" => georef = Some(read_georeferencing(&mut reader, &e)?),
+                b"symbols" | b"templates" | b"view" => {
+                    let _ = reader.read_to_end_into(e.name(), &mut Vec::new())?;
+                }
+                b"parts" => {
+                    parts_start_tag = Some(start..reader.buffer_position() as usize);
+                    current_part = attribute(&e, "current")?
+                        .and_then(|c| c.parse().ok())
+                        .unwrap_or(0);
+                }
+                b"part" => part_name = attribute(&e, "name")?.unwrap_or_default(),
+                b"objects" => {
+                    let start_tag = start..reader.buffer_position() as usize;
+                    let mut kept_objects = 0;
+
+                    loop {
+                        let object_start = reader.buffer_position() as usize;
+                        match reader.read_event_into(&mut buf)? {
+                            Event::Start(e) if e.name().as_ref() == b"object" => {
+                                let (symbol_id, tags) = scan_object(&mut reader, &e)?;
+                                if filter.matches(symbol_id, &tags) {
+                                    let mut end = reader.buffer_position() as usize;
+                                    if content[end..].starts_with('\n') {
+                                        end += 1;
+                                    }
+                                    removed_objects.push(object_start..end);
+                                } else {
+                                    kept_objects += 1;
+                                }
+                            }
+                            Event::Empty(e) if e.name().as_ref() == b"object" => {
+                                kept_objects += 1;
+                            }
+                            Event::End(e) if e.name().as_ref() == b"objects" => {
+                                objects_elements.push(ObjectsElement {
+                                    part_name: part_name.clone(),
+                                    start_tag,
+                                    is_empty: false,
+                                    end_tag: object_start,
+                                    kept_objects,
+                                });
+                                break;
+                            }
+                            Event::Eof => {
+                                return Err(OmapError::InvalidFile(
+                                    "unexpected end of file in objects".to_string(),
+                                ))
+                            }
+                            _ => (),
+                        }
+                        buf.clear();
+                    }
+                }
+                _ => (),
+            },
+            Event::Empty(e) if e.name().as_ref() == b"objects" => {
+                objects_elements.push(ObjectsElement {
+                    part_name: part_name.clone(),
+                    start_tag: start..reader.buffer_position() as usize,
+                    is_empty: true,
+                    end_tag: reader.buffer_position() as usize,
+                    kept_objects: 0,
+                });
+            }
+            Event::End(e) if e.name().as_ref() == b"parts" => parts_end_tag = Some(start),
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    let Some(georef) = georef else {
+        return Err(OmapError::InvalidFile(
+            "the georeferencing is missing".to_string(),
+        ));
+    };
+    let (Some(parts_start_tag), Some(parts_end_tag)) = (parts_start_tag, parts_end_tag) else {
+        return Err(OmapError::InvalidFile(
+            "the map parts are missing".to_string(),
+        ));
+    };
+
+    let symbol_set = SymbolSet::from_xml(content)?;
+    Ok(MapScan {
+        georef,
+        symbol_set,
+        removed_objects,
+        objects_elements,
+        parts_start_tag,
+        parts_end_tag,
+        current_part,
+    })
+}
+
+fn scan_object(
+    reader: &mut Reader<&[u8]>,
+    start: &BytesStart<'_>,
+) -> OmapResult<(Option<i32>, HashMap<String, String>)> {
+    let symbol_id = attribute(start, "symbol")?.and_then(|s| s.parse().ok());
+    let mut tags = HashMap::new();
+
+    let mut tag_key = None;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if e.name().as_ref() == b"t" => {
+                tag_key = Some(attribute(&e, "k")?.unwrap_or_default());
+            }
+            Event::Text(t) => {
+                if let Some(key) = tag_key.take() {
+                    let _ = tags.insert(key, t.unescape()?.into_owned());
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                b"t" => {
+                    if let Some(key) = tag_key.take() {
+                        let _ = tags.insert(key, String::new());
+                    }
+                }
+                b"object" => break,
+                _ => (),
+            },
+            Event::Eof => {
+                return Err(OmapError::InvalidFile(
+                    "unexpected end of file in object".to_string(),
+                ))
+            }
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok((symbol_id, tags))
+}
@@ -0,0 +1,161 @@
+use std::{
+    fmt::Display,
+    io::{self, Write},
+};
+
+/// A minimal streaming xml writer
+///
+/// All attribute values and text content are escaped, and characters that are not allowed in xml 1.0 are dropped
+pub(crate) struct XmlWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> XmlWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        XmlWriter { inner }
+    }
+
+    /// Write the start tag of an element with its attributes
+    pub(crate) fn start(
+        &mut self,
+        name: &str,
+        attributes: &[(&str, &dyn Display)],
+    ) -> io::Result<()> {
+        self.write_tag(name, attributes)?;
+        self.inner.write_all(b">")
+    }
+
+    /// Write an element without content with its attributes
+    pub(crate) fn empty(
+        &mut self,
+        name: &str,
+        attributes: &[(&str, &dyn Display)],
+    ) -> io::Result<()> {
+        self.write_tag(name, attributes)?;
+        self.inner.write_all(b"/>")
+    }
+
+    /// Write the end tag of an element
+    pub(crate) fn end(&mut self, name: &str) -> io::Result<()> {
+        self.inner.write_all(b"</")?;
+        self.inner.write_all(name.as_bytes())?;
+        self.inner.write_all(b">")
+    }
+
+    /// Write an element with only text content
+    pub(crate) fn element(
+        &mut self,
+        name: &str,
+        attributes: &[(&str, &dyn Display)],
+        text: &str,
+    ) -> io::Result<()> {
+        self.start(name, attributes)?;
+        self.text(text)?;
+        self.end(name)
+    }
+
+    /// Write escaped text content
+    pub(crate) fn text(&mut self, text: &str) -> io::Result<()> {
+        write_escaped(&mut self.inner, text, false)
+    }
+
+    /// Write bytes as is, they must already be valid xml
+    pub(crate) fn raw(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.inner.write_all(bytes)
+    }
+
+    pub(crate) fn newline(&mut self) -> io::Result<()> {
+        self.inner.write_all(b"\n")
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    fn write_tag(&mut self, name: &str, attributes: &[(&str, &dyn Display)]) -> io::Result<()> {
+        self.inner.write_all(b"<")?;
+        self.inner.write_all(name.as_bytes())?;
+        for (key, value) in attributes {
+            self.inner.write_all(b" ")?;
+            self.inner.write_all(key.as_bytes())?;
+            self.inner.write_all(b"=\"")?;
+            write_escaped(&mut self.inner, &value.to_string(), true)?;
+            self.inner.write_all(b"\"")?;
+        }
+        Ok(())
+    }
+}
+
+fn write_escaped<W: Write>(w: &mut W, s: &str, in_attribute: bool) -> io::Result<()> {
+    let bytes = s.as_bytes();
+    let mut last = 0;
+    for (i, c) in s.char_indices() {
+        let escaped = match c {
+            '&' => "&amp;",
+            '<' => "&lt;",
+            '>' => "&gt;",
+            '"' if in_attribute => "&quot;",
+            // whitespace in attributes is normalized to spaces by parsers unless escaped
+            '\t' if in_attribute => "&#9;",
+            '\n' if in_attribute => "&#10;",
+            '\r' => "&#13;",
+            // not allowed in xml 1.0, not even as character references
+            '\u{0}'..='\u{8}'
+            | '\u{b}'
+            | '\u{c}'
+            | '\u{e}'..='\u{1f}'
+            | '\u{fffe}'
+            | '\u{ffff}' => "",
+            _ => continue,
+        };
+        w.write_all(&bytes[last..i])?;
+        w.write_all(escaped.as_bytes())?;
+        last = i + c.len_utf8();
+    }
+    w.write_all(&bytes[last..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XmlWriter;
+    use quick_xml::{events::Event, Reader};
+
+    // write an element with the value as attribute and text and parse them back
+    fn round_trip(value: &str) -> (String, String) {
+        let mut f = XmlWriter::new(Vec::new());
+        f.element("t", &[("k", &value)], value).unwrap();
+        let xml = String::from_utf8(f.inner).unwrap();
+        // parsers normalize literal whitespace in attributes to spaces
+        let start_tag = &xml[..xml.find('>').unwrap()];
+        assert!(!start_tag.contains(['\t', '\n', '\r']), "{start_tag}");
+
+        let mut reader = Reader::from_str(&xml);
+        let Event::Start(start) = reader.read_event().unwrap() else {
+            panic!("no start tag in {xml}");
+        };
+        let attribute = start
+            .try_get_attribute("k")
+            .unwrap()
+            .unwrap()
+            .unescape_value()
+            .unwrap()
+            .into_owned();
+        let Event::Text(text) = reader.read_event().unwrap() else {
+            panic!("no text in {xml}");
+        };
+        (attribute, text.unescape().unwrap().into_owned())
+    }
+
+    #[test]
+    fn escapes_markup() {
+        let value = r#"a & b < c > d "e" 'f' &amp;"#;
+        assert_eq!(round_trip(value), (value.to_string(), value.to_string()));
+    }
+
+    #[test]
+    fn escapes_whitespace_and_drops_invalid_characters() {
+        let value = "tab\there\nnew line\r\u{0}\u{1}\u{b}\u{1f}\u{fffe}end æøå";
+        let expected = "tab\there\nnew line\rend æøå".to_string();
+        assert_eq!(round_trip(value), (expected.clone(), expected));
+    }
+}
@@ -0,0 +1,132 @@
+#![cfg(feature = "geo_ref")]
+
+use geo_types::{Coord, LineString, Point, Polygon};
+use omap::{
+    objects::{AreaObject, MapObject, PointObject},
+    symbols::{AreaSymbol, PointSymbol, Symbol},
+    CoordinateMode, Crs, Omap, OmapError, Scale,
+};
+
+// 60°N on the central meridian of a UTM zone
+const CENTRAL: Coord = Coord {
+    x: 500_000.,
+    y: 6_651_411.19,
+};
+
+fn map(mode: CoordinateMode) -> Omap {
+    let mut omap = Omap::new(CENTRAL, Scale::S10_000, Some(Crs::Epsg(25832)), None).unwrap();
+    omap.set_coordinate_mode(mode);
+    omap
+}
+
+fn knoll(x: f64, y: f64) -> PointObject {
+    PointObject::from_point(Point::new(x, y), PointSymbol::DotKnoll, 0.)
+}
+
+fn points(omap: &Omap) -> Vec<(Coord, f64)> {
+    omap.objects()[&Symbol::from(PointSymbol::DotKnoll)]
+        .iter()
+        .map(|o| match o {
+            MapObject::PointObject(p) => (p.point.0, p.rotation),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+fn assert_close(a: Coord, b: Coord, tolerance: f64) {
+    assert!((a - b).x.hypot((a - b).y) < tolerance, "{a:?} != {b:?}");
+}
+
+#[test]
+fn geographic_to_utm() {
+    let mut omap = map(CoordinateMode::Relative);
+    omap.add_object_in_crs(knoll(9., 60.), Crs::Epsg(4326))
+        .unwrap();
+    // the same position in the neighbouring zone
+    omap.add_object_in_crs(knoll(15., 60.), Crs::Epsg(4326))
+        .unwrap();
+    omap.add_object_in_crs(knoll(CENTRAL.x, CENTRAL.y), Crs::Epsg(25833))
+        .unwrap();
+
+    let points = points(&omap);
+    assert_close(points[0].0, Coord::zero(), 0.01);
+    assert!(points[0].1.abs() < 1e-6);
+
+    // 6° east of the central meridian at 60°N the convergence is about 6° * sin(60°)
+    assert_close(points[1].0, points[2].0, 0.01);
+    assert!(points[1].0.x > 300_000.);
+    for (_, rotation) in &points[1..] {
+        assert!(
+            (rotation.to_degrees().abs() - 5.2).abs() < 0.05,
+            "{}",
+            rotation.to_degrees()
+        );
+    }
+    assert!((points[1].1 - points[2].1).abs() < 1e-4);
+}
+
+#[test]
+fn into_the_coordinate_mode_of_the_map() {
+    let mut absolute = map(CoordinateMode::Absolute);
+    absolute
+        .add_object_in_crs(knoll(9.001, 60.001), Crs::Epsg(4326))
+        .unwrap();
+    let mut relative = map(CoordinateMode::Relative);
+    relative
+        .add_object_in_crs(knoll(9.001, 60.001), Crs::Epsg(4326))
+        .unwrap();
+    assert_close(
+        points(&absolute)[0].0 - CENTRAL,
+        points(&relative)[0].0,
+        1e-6,
+    );
+
+    let mut geographic = map(CoordinateMode::Geographic);
+    geographic
+        .add_object_in_crs(knoll(CENTRAL.x + 100., CENTRAL.y + 100.), Crs::Epsg(25832))
+        .unwrap();
+    let (c, _) = points(&geographic)[0];
+    assert!(c.x > 9. && c.x < 9.01 && c.y > 60. && c.y < 60.01, "{c:?}");
+}
+
+#[test]
+fn area_patterns_are_rotated() {
+    let mut omap = map(CoordinateMode::Relative);
+    let square = Polygon::new(
+        LineString::from(vec![
+            (15., 60.),
+            (15.001, 60.),
+            (15.001, 60.001),
+            (15., 60.),
+        ]),
+        vec![],
+    );
+    omap.add_object_in_crs(
+        AreaObject::from_polygon(square, AreaSymbol::RoughOpenLand, 0.),
+        Crs::Epsg(4326),
+    )
+    .unwrap();
+    let MapObject::AreaObject(area) = &omap.objects()[&AreaSymbol::RoughOpenLand.into()][0] else {
+        unreachable!()
+    };
+    assert!((area.pattern_rotation.to_degrees().abs() - 5.2).abs() < 0.05);
+}
+
+#[test]
+fn failed_transformations_add_no_objects() {
+    let mut omap = map(CoordinateMode::Relative);
+    assert!(matches!(
+        omap.add_objects_in_crs(
+            [knoll(9., 60.), knoll(9., 60.)],
+            Crs::Proj("+proj=unknown".to_string())
+        ),
+        Err(OmapError::Proj(_))
+    ));
+    assert!(omap.objects().is_empty());
+
+    let mut without_crs = Omap::new(CENTRAL, Scale::S10_000, None, None).unwrap();
+    assert!(matches!(
+        without_crs.add_object_in_crs(knoll(9., 60.), Crs::Epsg(4326)),
+        Err(OmapError::MissingCrs)
+    ));
+}
@@ -0,0 +1,139 @@
+use geo_types::Coord;
+use omap::{Omap, Scale};
+#[cfg(feature = "geo_ref")]
+use {
+    chrono::NaiveDate,
+    omap::{Crs, GeoidGrid, OmapError},
+};
+
+const OSLO: Coord = Coord {
+    x: 597_000.,
+    y: 6_643_000.,
+};
+
+// the attributes of the georeferencing element of the written map
+fn georeferencing(omap: &Omap, attribute: &str) -> f64 {
+    let mut content = Vec::new();
+    omap.write_to(&mut content, None).unwrap();
+    let content = String::from_utf8(content).unwrap();
+    let start = content.find("<georeferencing ").unwrap();
+    let element = &content[start..start + content[start..].find('>').unwrap()];
+    let pattern = format!(" {attribute}=\"");
+    let value = &element[element.find(&pattern).unwrap() + pattern.len()..];
+    value[..value.find('"').unwrap()].parse().unwrap()
+}
+
+#[cfg(feature = "geo_ref")]
+#[test]
+fn fixed_declination_dates() {
+    let build = |year| {
+        Omap::builder(OSLO, Scale::S10_000)
+            .crs(Crs::Epsg(25832))
+            .declination_date(NaiveDate::from_ymd_opt(year, 6, 1).unwrap())
+            .build()
+            .unwrap()
+    };
+    let first = build(2021);
+    let again = build(2021);
+    for attribute in ["declination", "grivation", "grid_scale_factor"] {
+        assert_eq!(
+            georeferencing(&first, attribute),
+            georeferencing(&again, attribute)
+        );
+    }
+    // the declination in Oslo grows by about a tenth of a degree per year
+    let later = build(2024);
+    let change = georeferencing(&later, "declination") - georeferencing(&first, "declination");
+    assert!(change > 0.1 && change < 1., "{change}");
+}
+
+#[cfg(feature = "geo_ref")]
+#[test]
+fn overridden_parameters() {
+    let computed = Omap::builder(OSLO, Scale::S10_000)
+        .crs(Crs::Epsg(25832))
+        .build()
+        .unwrap();
+    let convergence =
+        georeferencing(&computed, "declination") - georeferencing(&computed, "grivation");
+    assert!(convergence > 1. && convergence < 2., "{convergence}");
+
+    let declination = Omap::builder(OSLO, Scale::S10_000)
+        .crs(Crs::Epsg(25832))
+        .declination(4_f64.to_radians())
+        .build()
+        .unwrap();
+    assert!((georeferencing(&declination, "declination") - 4.).abs() < 1e-9);
+    assert!((georeferencing(&declination, "grivation") - (4. - convergence)).abs() < 1e-9);
+
+    // the declination is derived from the grivation and the convergence
+    let grivation = Omap::builder(OSLO, Scale::S10_000)
+        .crs(Crs::Epsg(25832))
+        .grivation(3_f64.to_radians())
+        .build()
+        .unwrap();
+    assert!((georeferencing(&grivation, "grivation") - 3.).abs() < 1e-9);
+    assert!((georeferencing(&grivation, "declination") - (3. + convergence)).abs() < 1e-9);
+
+    // the given grid scale factor is still combined with the elevation scale factor
+    let scale_factor = Omap::builder(OSLO, Scale::S10_000)
+        .crs(Crs::Epsg(25832))
+        .meters_above_sea_level(1000.)
+        .grid_scale_factor(0.9996)
+        .build()
+        .unwrap();
+    let elevation_scale_factor = georeferencing(&scale_factor, "auxiliary_scale_factor");
+    assert!(elevation_scale_factor < 1. && elevation_scale_factor > 0.9998);
+    assert!(
+        (georeferencing(&scale_factor, "grid_scale_factor") - 0.9996 * elevation_scale_factor)
+            .abs()
+            < 1e-12
+    );
+}
+
+#[test]
+fn overridden_parameters_without_crs() {
+    let omap = Omap::builder(OSLO, Scale::S10_000)
+        .declination(2_f64.to_radians())
+        .grivation(1_f64.to_radians())
+        .grid_scale_factor(0.9996)
+        .build()
+        .unwrap();
+    assert!((georeferencing(&omap, "declination") - 2.).abs() < 1e-9);
+    assert!((georeferencing(&omap, "grivation") - 1.).abs() < 1e-9);
+    assert!((georeferencing(&omap, "grid_scale_factor") - 0.9996).abs() < 1e-12);
+}
+
+#[cfg(feature = "geo_ref")]
+#[test]
+fn heights_above_the_geoid() {
+    let build = |meters_above_sea_level, geoid: Option<GeoidGrid>| {
+        let mut builder = Omap::builder(OSLO, Scale::S10_000)
+            .crs(Crs::Epsg(25832))
+            .meters_above_sea_level(meters_above_sea_level);
+        if let Some(geoid) = geoid {
+            builder = builder.geoid(geoid);
+        }
+        builder.build()
+    };
+    // a geoid 40 m above the ellipsoid around Oslo
+    let geoid = GeoidGrid::from_ascii("59 61 10 12 1 1\n40 40 40\n40 40 40\n40 40 40").unwrap();
+
+    let with_geoid = build(100., Some(geoid)).unwrap();
+    let above_ellipsoid = build(140., None).unwrap();
+    let without_geoid = build(100., None).unwrap();
+    assert_eq!(
+        georeferencing(&with_geoid, "auxiliary_scale_factor"),
+        georeferencing(&above_ellipsoid, "auxiliary_scale_factor")
+    );
+    assert!(
+        georeferencing(&with_geoid, "auxiliary_scale_factor")
+            < georeferencing(&without_geoid, "auxiliary_scale_factor")
+    );
+
+    let elsewhere = GeoidGrid::from_ascii("0 1 0 1 1 1\n40 40\n40 40").unwrap();
+    assert!(matches!(
+        build(100., Some(elsewhere)),
+        Err(OmapError::OutsideGeoidGrid)
+    ));
+}
@@ -0,0 +1,58 @@
+use geo_types::{Coord, LineString, Polygon};
+use omap::{
+    objects::{AreaObject, LineObject, MapObject},
+    symbols::{AreaSymbol, LineSymbol, PointSymbol},
+    CollapseRule, Omap, Scale,
+};
+
+fn collapsed_rotations(omap: &mut Omap, rule: CollapseRule) -> Vec<f64> {
+    assert_eq!(omap.collapse_to_points(&[rule]).unwrap(), 1);
+    omap.objects()[&rule.point_symbol.into()]
+        .iter()
+        .map(|o| match o {
+            MapObject::PointObject(p) => p.rotation,
+            _ => panic!("a collapsed object is not a point object"),
+        })
+        .collect()
+}
+
+#[test]
+fn points_follow_the_direction_of_lines() {
+    let rule = CollapseRule {
+        symbol: LineSymbol::Cliff.into(),
+        point_symbol: PointSymbol::MinimumCliff,
+        max_size: 100.,
+    };
+    for (end, rotation) in [
+        (Coord { x: 10., y: 10. }, 0.25),
+        (Coord { x: -10., y: -10. }, 1.25),
+        (Coord { x: 0., y: 10. }, 0.5),
+    ] {
+        let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+        omap.add_object(LineObject::from_line_string(
+            LineString::new(vec![Coord::zero(), end]),
+            LineSymbol::Cliff,
+        ));
+        let rotations = collapsed_rotations(&mut omap, rule);
+        assert!((rotations[0] - rotation * std::f64::consts::PI).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn points_follow_the_major_axis_of_areas() {
+    let rule = CollapseRule {
+        symbol: AreaSymbol::Marsh.into(),
+        point_symbol: PointSymbol::MinimumMarsh,
+        max_size: 1000.,
+    };
+    let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+    // a 4 x 20 m rectangle along the y axis
+    let rectangle = LineString::from(vec![(0., 0.), (4., 0.), (4., 20.), (0., 20.), (0., 0.)]);
+    omap.add_object(AreaObject::from_polygon(
+        Polygon::new(rectangle, vec![]),
+        AreaSymbol::Marsh,
+        0.,
+    ));
+    let rotations = collapsed_rotations(&mut omap, rule);
+    assert!((rotations[0] - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+}
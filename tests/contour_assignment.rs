@@ -0,0 +1,89 @@
+use geo_types::LineString;
+use omap::{
+    objects::{LineObject, MapObject, TagTrait},
+    symbols::{LineSymbol, SprintLineSymbol, Symbol},
+    Omap, Scale,
+};
+
+fn contour(symbol: LineSymbol, elevation: Option<f64>) -> LineObject {
+    let mut line =
+        LineObject::from_line_string(LineString::from(vec![(0., 0.), (10., 0.)]), symbol);
+    if let Some(elevation) = elevation {
+        line.add_elevation_tag(elevation);
+    }
+    line
+}
+
+// the elevations of the lines with a symbol, sorted
+fn elevations(omap: &Omap, symbol: impl Into<Symbol>) -> Vec<String> {
+    let mut elevations: Vec<String> = omap
+        .objects()
+        .get(&symbol.into())
+        .into_iter()
+        .flatten()
+        .map(|o| match o {
+            MapObject::LineObject(l) => l.tags.get("Elevation").cloned().unwrap_or_default(),
+            _ => unreachable!(),
+        })
+        .collect();
+    elevations.sort();
+    elevations
+}
+
+#[test]
+fn five_meter_contours_with_index_every_25_meters() {
+    let mut omap = Omap::new(Default::default(), Scale::S10_000, None, None).unwrap();
+    for elevation in [100., 105., 12.5, 7.3, 0.] {
+        omap.add_object(contour(LineSymbol::Contour, Some(elevation)));
+    }
+    // already on the right symbol, or without an elevation
+    omap.add_object(contour(LineSymbol::IndexContour, Some(125.)));
+    omap.add_object(contour(LineSymbol::FormLine, None));
+    omap.add_object(contour(LineSymbol::FormLine, Some(-2.5)));
+    // sprint contours stay sprint contours
+    omap.add_object(contour(
+        LineSymbol::Sprint(SprintLineSymbol::Contour),
+        Some(50.),
+    ));
+    // other lines are left alone
+    omap.add_object(contour(LineSymbol::Cliff, Some(7.3)));
+
+    let report = omap.assign_contour_symbols(5., 25.);
+    assert_eq!(report.num_reassigned, 5);
+    assert_eq!(report.mismatched_elevations, [7.3]);
+    assert_eq!(report.num_untagged, 1);
+
+    assert_eq!(
+        elevations(&omap, LineSymbol::IndexContour),
+        ["0.00", "100.00", "125.00"]
+    );
+    assert_eq!(elevations(&omap, LineSymbol::Contour), ["105.00"]);
+    assert_eq!(
+        elevations(&omap, LineSymbol::FormLine),
+        ["", "-2.50", "12.50"]
+    );
+    assert_eq!(elevations(&omap, LineSymbol::BasemapContour), ["7.30"]);
+    assert_eq!(
+        elevations(&omap, LineSymbol::Sprint(SprintLineSymbol::IndexContour)),
+        ["50.00"]
+    );
+    assert_eq!(elevations(&omap, LineSymbol::Cliff), ["7.30"]);
+
+    // assigning again changes nothing
+    let report = omap.assign_contour_symbols(5., 25.);
+    assert_eq!(report.num_reassigned, 0);
+    assert_eq!(report.mismatched_elevations, [7.3]);
+}
+
+#[test]
+fn decimal_equidistances() {
+    let mut omap = Omap::new(Default::default(), Scale::S4_000, None, None).unwrap();
+    for elevation in [2.5, 3.75, 12.5, 13.1] {
+        omap.add_object(contour(LineSymbol::Contour, Some(elevation)));
+    }
+    let report = omap.assign_contour_symbols(2.5, 12.5);
+    assert_eq!(report.mismatched_elevations, [13.1]);
+    assert_eq!(elevations(&omap, LineSymbol::IndexContour), ["12.50"]);
+    assert_eq!(elevations(&omap, LineSymbol::Contour), ["2.50"]);
+    assert_eq!(elevations(&omap, LineSymbol::FormLine), ["3.75"]);
+}
@@ -0,0 +1,168 @@
+use geo_types::{Coord, LineString};
+use omap::{
+    objects::{LineObject, MapObject, TagTrait, TextObject},
+    symbols::{LineSymbol, TextSymbol},
+    Omap, Scale,
+};
+
+fn contour(coords: Vec<Coord>, symbol: LineSymbol, elevation: f64) -> LineObject {
+    let mut line = LineObject::from_line_string(LineString::new(coords), symbol);
+    line.add_elevation_tag(elevation);
+    line
+}
+
+fn straight(from: Coord, to: Coord, n: usize) -> Vec<Coord> {
+    (0..=n)
+        .map(|i| from + (to - from) * (i as f64 / n as f64))
+        .collect()
+}
+
+fn circle(radius: f64, n: usize, clockwise: bool) -> Vec<Coord> {
+    let mut coords: Vec<Coord> = (0..n)
+        .map(|i| {
+            let a = i as f64 / n as f64 * std::f64::consts::TAU;
+            Coord {
+                x: radius * a.cos(),
+                y: radius * a.sin(),
+            }
+        })
+        .collect();
+    if clockwise {
+        coords.reverse();
+    }
+    coords.push(coords[0]);
+    coords
+}
+
+fn labels(omap: &Omap) -> Vec<&TextObject> {
+    omap.objects()[&TextSymbol::ContourValue.into()]
+        .iter()
+        .map(|o| match o {
+            MapObject::TextObject(t) => t,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+fn index_contours(omap: &Omap) -> Vec<&LineString> {
+    omap.objects()[&LineSymbol::IndexContour.into()]
+        .iter()
+        .map(|o| match o {
+            MapObject::LineObject(l) => &l.line,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+// the direction the top of a label faces
+fn up(label: &TextObject) -> Coord {
+    let rotation = label.rotation.unwrap();
+    Coord {
+        x: -rotation.sin(),
+        y: rotation.cos(),
+    }
+}
+
+fn length(line: &LineString) -> f64 {
+    line.0
+        .windows(2)
+        .map(|w| (w[1] - w[0]).x.hypot((w[1] - w[0]).y))
+        .sum()
+}
+
+#[test]
+fn labels_face_uphill() {
+    let west = Coord { x: 0., y: 0. };
+    let east = Coord { x: 400., y: 0. };
+    let below = Coord { x: 0., y: -40. };
+    // the neighbouring contour is either lower or higher, the index contour runs either way
+    for (from, to, neighbour_elevation, uphill) in [
+        (west, east, 95., 1.),
+        (east, west, 95., 1.),
+        (west, east, 105., -1.),
+        (east, west, 105., -1.),
+    ] {
+        let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+        omap.add_object(contour(
+            straight(from, to, 40),
+            LineSymbol::IndexContour,
+            100.,
+        ));
+        omap.add_object(contour(
+            straight(west + below, east + below, 40),
+            LineSymbol::Contour,
+            neighbour_elevation,
+        ));
+
+        assert_eq!(omap.add_contour_labels(1000.).unwrap(), 1);
+        let label = labels(&omap)[0];
+        assert_eq!(label.text, "100");
+        assert!(label.point.y().abs() < 1e-9);
+        assert!((up(label).y - uphill).abs() < 1e-6, "{:?}", up(label));
+    }
+}
+
+#[test]
+fn contours_are_cut_beneath_labels() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+    let line = straight(Coord::zero(), Coord { x: 400., y: 0. }, 40);
+    omap.add_object(contour(line, LineSymbol::IndexContour, 100.));
+
+    assert_eq!(omap.add_contour_labels(1000.).unwrap(), 1);
+    let label = labels(&omap)[0];
+    let pieces = index_contours(&omap);
+    assert_eq!(pieces.len(), 2);
+    // the pieces keep their tags
+    assert!(omap.objects()[&LineSymbol::IndexContour.into()]
+        .iter()
+        .all(|o| matches!(o, MapObject::LineObject(l) if l.tags["Elevation"] == "100.00")));
+
+    // the gap is centred on the label and as long as the label with its margins
+    let mut ends: Vec<f64> = pieces
+        .iter()
+        .flat_map(|p| [p.0[0].x, p.0[p.0.len() - 1].x])
+        .collect();
+    ends.sort_by(f64::total_cmp);
+    let gap = ends[2] - ends[1];
+    assert!(((ends[1] + ends[2]) / 2. - label.point.x()).abs() < 1e-6);
+    // 3 digits of 1.15 mm and 2 x 0.3 mm margin, enlarged to 150% at 10 m per mm
+    assert!((gap - (3. * 1.15 + 0.6) * 1.5 * 10.).abs() < 1e-6, "{gap}");
+    assert!((pieces.iter().map(|p| length(p)).sum::<f64>() + gap - 400.).abs() < 1e-6);
+}
+
+#[test]
+fn closed_contours_face_their_higher_side() {
+    // counter clockwise loops are knolls and clockwise loops depressions without neighbouring contours
+    for (clockwise, inward) in [(false, 1.), (true, -1.)] {
+        let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+        omap.add_object(contour(
+            circle(300., 64, clockwise),
+            LineSymbol::IndexContour,
+            100.,
+        ));
+        assert!(omap.add_contour_labels(1000.).unwrap() >= 1);
+        for label in labels(&omap) {
+            let to_center = -label.point.0 / 300.;
+            let facing = up(label).x * to_center.x + up(label).y * to_center.y;
+            assert!((facing - inward).abs() < 0.01, "{facing}");
+        }
+    }
+}
+
+#[test]
+fn crowded_contours_get_no_labels() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+    omap.add_object(contour(
+        straight(Coord::zero(), Coord { x: 400., y: 0. }, 40),
+        LineSymbol::IndexContour,
+        100.,
+    ));
+    // a contour closer than the label height all along
+    omap.add_object(contour(
+        straight(Coord { x: 0., y: 5. }, Coord { x: 400., y: 5. }, 40),
+        LineSymbol::Contour,
+        105.,
+    ));
+    assert_eq!(omap.add_contour_labels(1000.).unwrap(), 0);
+    assert_eq!(index_contours(&omap).len(), 1);
+}
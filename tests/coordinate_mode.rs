@@ -0,0 +1,104 @@
+use geo_types::{Coord, LineString, Point};
+#[cfg(feature = "geo_ref")]
+use omap::Crs;
+use omap::{
+    objects::{LineObject, PointObject},
+    symbols::{LineSymbol, PointSymbol},
+    CoordinateMode, Omap, Scale,
+};
+
+fn objects_xml(omap: &Omap) -> String {
+    let mut content = Vec::new();
+    omap.write_to(&mut content, None).unwrap();
+    let content = String::from_utf8(content).unwrap();
+    let (_, objects) = content.split_once("<parts").unwrap();
+    objects.to_string()
+}
+
+fn add_objects(omap: &mut Omap, offset: Coord) {
+    omap.add_object(PointObject::from_point(
+        Point::from(offset + Coord { x: 10., y: 20. }),
+        PointSymbol::DotKnoll,
+        0.,
+    ));
+    omap.add_object(LineObject::from_line_string(
+        LineString::from(vec![offset, offset + Coord { x: 100., y: -50. }]),
+        LineSymbol::Contour,
+    ));
+}
+
+#[test]
+fn absolute_coordinates() {
+    let ref_point = Coord {
+        x: 597_000.,
+        y: 6_643_000.,
+    };
+    let mut relative = Omap::new(ref_point, Scale::S10_000, None, None).unwrap();
+    add_objects(&mut relative, Coord::zero());
+
+    let mut absolute = Omap::new(ref_point, Scale::S10_000, None, None).unwrap();
+    absolute.set_coordinate_mode(CoordinateMode::Absolute);
+    add_objects(&mut absolute, ref_point);
+
+    let written = objects_xml(&relative);
+    assert!(written.contains("1000 -2000;"));
+    assert!(written.contains("10000 5000;"));
+    assert_eq!(written, objects_xml(&absolute));
+
+    // the objects are kept in the coordinates they were added in
+    absolute.set_coordinate_mode(CoordinateMode::Relative);
+    assert_ne!(written, objects_xml(&absolute));
+}
+
+#[cfg(feature = "geo_ref")]
+#[test]
+fn geographic_coordinates() {
+    // 9°E is the central meridian of UTM zone 32, where 60°N is 6_651_411.19 m north of the equator
+    let ref_point = Coord {
+        x: 500_000.,
+        y: 6_651_411.19,
+    };
+    let mut omap = Omap::new(ref_point, Scale::S10_000, Some(Crs::Epsg(25832)), None).unwrap();
+    omap.set_coordinate_mode(CoordinateMode::Geographic);
+    omap.add_object(PointObject::from_point(
+        Point::new(9., 60.),
+        PointSymbol::DotKnoll,
+        0.,
+    ));
+    assert!(objects_xml(&omap).contains(r#"<coords count="1">0 0;</coords>"#));
+
+    // a degree of latitude is about 111.4 km at 60°N, the map is rotated to magnetic north
+    omap.add_object(PointObject::from_point(
+        Point::new(9., 60.001),
+        PointSymbol::DotKnoll,
+        0.,
+    ));
+    let written = objects_xml(&omap);
+    let (_, coords) = written.rsplit_once(r#"<coords count="1">"#).unwrap();
+    let (x, y) = coords[..coords.find(';').unwrap()].split_once(' ').unwrap();
+    let (x, y): (f64, f64) = (x.parse().unwrap(), y.parse().unwrap());
+    assert!(y < 0.);
+    assert!((x.hypot(y) / 100. - 111.4).abs() < 0.1, "{x} {y}");
+
+    let mut without_crs = Omap::new(ref_point, Scale::S10_000, None, None).unwrap();
+    without_crs.set_coordinate_mode(CoordinateMode::Geographic);
+    add_objects(&mut without_crs, Coord::zero());
+    let mut content = Vec::new();
+    assert!(matches!(
+        without_crs.write_to(&mut content, None),
+        Err(omap::OmapError::MissingCrs)
+    ));
+}
+
+#[cfg(not(feature = "geo_ref"))]
+#[test]
+fn geographic_coordinates_need_the_feature() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+    omap.set_coordinate_mode(CoordinateMode::Geographic);
+    add_objects(&mut omap, Coord::zero());
+    let mut content = Vec::new();
+    assert!(matches!(
+        omap.write_to(&mut content, None),
+        Err(omap::OmapError::DisabledGeoReferencingFeature)
+    ));
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "geo_ref")]
+
+use geo_types::{Coord, LineString};
+use omap::{objects::LineObject, symbols::LineSymbol, CoordinateMode, Crs, Omap, OmapError, Scale};
+
+// 60°N on the central meridian of UTM zone 32
+const CENTRAL: Coord = Coord {
+    x: 500_000.,
+    y: 6_651_411.19,
+};
+
+// a map with a line from the ref point `half_width` meters east and north
+fn map(half_width: f64) -> Omap {
+    let mut omap = Omap::new(CENTRAL, Scale::S10_000, Some(Crs::Epsg(25832)), None).unwrap();
+    omap.add_object(LineObject::from_line_string(
+        LineString::from(vec![
+            Coord {
+                x: -half_width,
+                y: -half_width,
+            },
+            Coord {
+                x: half_width,
+                y: half_width,
+            },
+        ]),
+        LineSymbol::Contour,
+    ));
+    omap
+}
+
+#[test]
+fn distortion_grows_with_the_extent() {
+    let small = map(5_000.).distortion_report(5).unwrap();
+    let large = map(50_000.).distortion_report(5).unwrap();
+
+    assert!((small.ref_scale_factor - 0.9996).abs() < 1e-6);
+    assert!(small.ref_convergence.abs() < 1e-6);
+    assert_eq!(
+        large.extent.min(),
+        CENTRAL
+            - Coord {
+                x: 50_000.,
+                y: 50_000.
+            }
+    );
+    assert_eq!(
+        large.extent.max(),
+        CENTRAL
+            + Coord {
+                x: 50_000.,
+                y: 50_000.
+            }
+    );
+
+    // the scale factor grows with the squared distance from the central meridian
+    assert!(small.max_scale_factor_deviation > 0.);
+    let ratio = large.max_scale_factor_deviation / small.max_scale_factor_deviation;
+    assert!((ratio - 100.).abs() < 5., "{ratio}");
+    // k ≈ k0 * (1 + x² / 2R²) 50 km from the central meridian
+    assert!(
+        (large.max_scale_factor_deviation - 3.1e-5).abs() < 0.2e-5,
+        "{}",
+        large.max_scale_factor_deviation
+    );
+    assert!((large.max_scale_factor_deviation_at.x - CENTRAL.x).abs() == 50_000.);
+
+    // the convergence grows linearly with the longitude difference, ≈ 0.78° 50 km east at 60°N
+    let ratio = large.max_convergence_deviation / small.max_convergence_deviation;
+    assert!((ratio - 10.).abs() < 1., "{ratio}");
+    assert!(
+        (large.max_convergence_deviation.to_degrees() - 0.78).abs() < 0.05,
+        "{}",
+        large.max_convergence_deviation.to_degrees()
+    );
+}
+
+#[test]
+fn absolute_coordinates_and_empty_maps() {
+    let relative = map(5_000.).distortion_report(3).unwrap();
+    let mut absolute = Omap::new(CENTRAL, Scale::S10_000, Some(Crs::Epsg(25832)), None).unwrap();
+    absolute.set_coordinate_mode(CoordinateMode::Absolute);
+    absolute.add_object(LineObject::from_line_string(
+        LineString::from(vec![
+            CENTRAL
+                - Coord {
+                    x: 5_000.,
+                    y: 5_000.,
+                },
+            CENTRAL
+                + Coord {
+                    x: 5_000.,
+                    y: 5_000.,
+                },
+        ]),
+        LineSymbol::Contour,
+    ));
+    assert_eq!(absolute.distortion_report(3).unwrap(), relative);
+
+    let empty = Omap::new(CENTRAL, Scale::S10_000, Some(Crs::Epsg(25832)), None)
+        .unwrap()
+        .distortion_report(3)
+        .unwrap();
+    assert_eq!(empty.max_scale_factor_deviation, 0.);
+    assert_eq!(empty.max_convergence_deviation, 0.);
+
+    let without_crs = Omap::new(CENTRAL, Scale::S10_000, None, None).unwrap();
+    assert!(matches!(
+        without_crs.distortion_report(3),
+        Err(OmapError::MissingCrs)
+    ));
+}
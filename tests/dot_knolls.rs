@@ -0,0 +1,79 @@
+use geo_types::{Coord, LineString};
+use omap::{
+    objects::LineObject,
+    symbols::{LineSymbol, SprintLineSymbol, SprintPointSymbol, Symbol},
+    Omap, Scale,
+};
+
+// a closed loop around the center with the radii along the axes
+fn ellipse(center: Coord, rx: f64, ry: f64) -> LineString {
+    let mut coords: Vec<Coord> = (0..40)
+        .map(|i| {
+            let a = i as f64 / 40. * std::f64::consts::TAU;
+            center
+                + Coord {
+                    x: rx * a.cos(),
+                    y: ry * a.sin(),
+                }
+        })
+        .collect();
+    coords.push(coords[0]);
+    LineString::new(coords)
+}
+
+fn count(omap: &Omap, symbol: impl Into<Symbol>) -> usize {
+    omap.objects().get(&symbol.into()).map_or(0, Vec::len)
+}
+
+#[test]
+fn sprint_contours() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S4_000, None, None).unwrap();
+    let contour = LineSymbol::Sprint(SprintLineSymbol::Contour);
+    omap.add_object(LineObject::from_line_string(
+        ellipse(Coord::zero(), 10., 10.),
+        contour,
+    ));
+    omap.add_object(LineObject::from_line_string(
+        ellipse(Coord { x: 100., y: 0. }, 20., 5.),
+        contour,
+    ));
+    omap.make_dotknolls_and_depressions(50., 1000., 1.5, 0., 0.)
+        .unwrap();
+
+    assert_eq!(count(&omap, contour), 0);
+    assert_eq!(count(&omap, SprintPointSymbol::DotKnoll), 1);
+    assert_eq!(count(&omap, SprintPointSymbol::ElongatedDotKnoll), 1);
+}
+
+#[cfg(feature = "geo_ref")]
+#[test]
+fn geographic_contours() {
+    let mut omap = Omap::builder(
+        Coord {
+            x: 597_000.,
+            y: 6_643_000.,
+        },
+        Scale::S10_000,
+    )
+    .crs(25832)
+    .build()
+    .unwrap();
+    omap.set_coordinate_mode(omap::CoordinateMode::Geographic);
+
+    // a round knoll of 10 m radius, which is twice as wide as high in degrees
+    let lat: f64 = 59.91;
+    let meters_per_degree = 111_320.;
+    omap.add_object(LineObject::from_line_string(
+        ellipse(
+            Coord { x: 10.75, y: lat },
+            10. / (meters_per_degree * lat.to_radians().cos()),
+            10. / meters_per_degree,
+        ),
+        LineSymbol::Contour,
+    ));
+    omap.make_dotknolls_and_depressions(50., 1000., 1.5, 0., 0.)
+        .unwrap();
+
+    assert_eq!(count(&omap, LineSymbol::Contour), 0);
+    assert_eq!(count(&omap, omap::symbols::PointSymbol::DotKnoll), 1);
+}
@@ -0,0 +1,96 @@
+use geo_types::{Coord, LineString, Point};
+#[cfg(feature = "geo_ref")]
+use omap::Crs;
+use omap::{
+    objects::{LineObject, MapObject, PointObject, TagTrait},
+    symbols::{LineSymbol, PointSymbol},
+    CoordinateMode, Omap, Scale,
+};
+
+fn contour(from: Coord, to: Coord, symbol: LineSymbol, elevation: f64) -> MapObject {
+    let mut line = LineObject::from_line_string(LineString::from(vec![from, to]), symbol);
+    line.add_elevation_tag(elevation);
+    line.into()
+}
+
+fn objects() -> Vec<MapObject> {
+    let mut knoll =
+        PointObject::from_point(Point::new(597_180., 6_643_020.), PointSymbol::DotKnoll, 0.);
+    // only the elevations of contours are used
+    knoll.add_elevation_tag(1000.);
+    vec![
+        contour(
+            Coord {
+                x: 596_930.,
+                y: 6_642_840.,
+            },
+            Coord {
+                x: 597_020.,
+                y: 6_643_100.,
+            },
+            LineSymbol::Contour,
+            100.,
+        ),
+        contour(
+            Coord {
+                x: 597_000.,
+                y: 6_642_900.,
+            },
+            Coord {
+                x: 597_100.,
+                y: 6_642_950.,
+            },
+            LineSymbol::IndexContour,
+            125.,
+        ),
+        knoll.into(),
+    ]
+}
+
+#[test]
+fn ref_point_at_the_rounded_centre() {
+    // the extent is 596_930..597_180 and 6_642_840..6_643_100
+    let omap = Omap::from_objects(objects(), Scale::S10_000, None, false).unwrap();
+    assert_eq!(
+        omap.get_ref_point(),
+        Coord {
+            x: 597_100.,
+            y: 6_643_000.
+        }
+    );
+    assert_eq!(omap.get_coordinate_mode(), CoordinateMode::Absolute);
+    assert_eq!(omap.objects().values().flatten().count(), 3);
+
+    // the objects are written relative the ref point
+    let mut content = Vec::new();
+    omap.write_to(&mut content, None).unwrap();
+    let content = String::from_utf8(content).unwrap();
+    assert!(content.contains(r#"<coords count="1">8000 -2000;</coords>"#));
+
+    let empty = Omap::from_objects(Vec::<MapObject>::new(), Scale::S10_000, None, false).unwrap();
+    assert_eq!(empty.get_ref_point(), Coord::zero());
+}
+
+#[cfg(feature = "geo_ref")]
+#[test]
+fn elevation_from_the_contours() {
+    let scale_factor = |omap: &Omap| {
+        let mut content = Vec::new();
+        omap.write_to(&mut content, None).unwrap();
+        let content = String::from_utf8(content).unwrap();
+        let start = content.find(r#"auxiliary_scale_factor=""#).unwrap() + 24;
+        content[start..start + content[start..].find('"').unwrap()].to_string()
+    };
+
+    let omap = Omap::from_objects(objects(), Scale::S10_000, Some(Crs::Epsg(25832)), true).unwrap();
+    let expected = Omap::builder(omap.get_ref_point(), Scale::S10_000)
+        .crs(Crs::Epsg(25832))
+        .meters_above_sea_level(112.5)
+        .build()
+        .unwrap();
+    assert_eq!(scale_factor(&omap), scale_factor(&expected));
+
+    let without_elevation =
+        Omap::from_objects(objects(), Scale::S10_000, Some(Crs::Epsg(25832)), false).unwrap();
+    assert_eq!(scale_factor(&without_elevation), "1");
+}
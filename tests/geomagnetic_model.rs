@@ -0,0 +1,103 @@
+#![cfg(feature = "geo_ref")]
+
+use chrono::{Datelike, NaiveDate};
+use omap::{GeomagneticModel, OmapError};
+use world_magnetic_model::{
+    time::Date,
+    uom::si::{
+        angle::{degree, radian},
+        f32::{Angle, Length},
+        length::meter,
+    },
+    GeomagneticField,
+};
+
+const WMM2025: &str = include_str!("data/WMM2025.COF");
+
+fn built_in_declination(lon: f64, lat: f64, height: f64, date: NaiveDate) -> f64 {
+    let date = Date::from_ordinal_date(date.year(), date.ordinal() as u16).unwrap();
+    GeomagneticField::new(
+        Length::new::<meter>(height as f32),
+        Angle::new::<degree>(lat as f32),
+        Angle::new::<degree>(lon as f32),
+        date,
+    )
+    .unwrap()
+    .declination()
+    .get::<radian>() as f64
+}
+
+#[test]
+fn wmm_matches_the_built_in_model() {
+    let model = GeomagneticModel::from_cof(WMM2025).unwrap();
+    assert_eq!(model.validity(), (2025., 2030.));
+
+    for (lon, lat, height) in [
+        (10.75, 59.91, 0.),
+        (-122.4, 37.8, 100.),
+        (151.2, -33.9, 0.),
+        (-70., -60., 2000.),
+        (100., 80., 0.),
+    ] {
+        for date in [(2025, 1, 1), (2027, 7, 15), (2029, 12, 31)] {
+            let date = NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap();
+            let declination = model.declination(lon, lat, height, date).unwrap();
+            let expected = built_in_declination(lon, lat, height, date);
+            assert!(
+                (declination - expected).abs() < 0.001_f64.to_radians(),
+                "{declination} != {expected} at {lon} {lat} on {date}"
+            );
+        }
+    }
+}
+
+#[test]
+fn dates_outside_the_model() {
+    let model = GeomagneticModel::from_cof(WMM2025).unwrap();
+    let date = NaiveDate::from_ymd_opt(2031, 1, 1).unwrap();
+    assert!(matches!(
+        model.declination(10., 60., 0., date),
+        Err(OmapError::DateOutsideOfModelEpoch(..))
+    ));
+}
+
+#[test]
+fn malformed_models() {
+    let header = "    2025.0            WMM-2025     11/13/2024\n";
+    let nines = "999999999999999999999999999999999999999999999999\n";
+    let coefficient =
+        |n: usize, m: usize| format!("{n:3}{m:3}    -1000.0      100.0        1.0        1.0\n");
+    let incomplete = [
+        // the last degree is missing an order
+        format!(
+            "{header}{}{}{}{nines}",
+            coefficient(1, 0),
+            coefficient(1, 1),
+            coefficient(2, 0)
+        ),
+        // a degree is skipped
+        format!(
+            "{header}{}{}{}{nines}",
+            coefficient(1, 0),
+            coefficient(1, 1),
+            coefficient(3, 0)
+        ),
+    ];
+    let malformed = [
+        String::new(),
+        header.to_string(),
+        format!("{}{nines}", coefficient(1, 0)),
+        format!("{header}  1  2    -1000.0      100.0        1.0        1.0\n"),
+        format!("{header}  1  0    -1000.0      abc        1.0        1.0\n"),
+        format!("{header}  1  0    -1000.0\n"),
+    ];
+    for content in incomplete.iter().chain(malformed.iter()) {
+        assert!(
+            matches!(
+                GeomagneticModel::from_cof(content),
+                Err(OmapError::InvalidGeomagneticModel(_))
+            ),
+            "{content}"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use geo_types::{Coord, Point};
+use omap::{objects::PointObject, symbols::PointSymbol, CoordinateMode, Omap, Scale};
+#[cfg(feature = "geo_ref")]
+use omap::{Crs, OmapError};
+
+const REF_POINT: Coord = Coord {
+    x: 597_000.,
+    y: 6_643_000.,
+};
+
+fn assert_close(a: Coord, b: Coord, tolerance: f64) {
+    assert!((a - b).x.hypot((a - b).y) < tolerance, "{a:?} != {b:?}");
+}
+
+#[test]
+fn millimetres_on_paper() {
+    let omap = Omap::new(REF_POINT, Scale::S10_000, None, None).unwrap();
+    let c = REF_POINT + Coord { x: 100., y: -200. };
+    assert_close(omap.projected_to_map(c), Coord { x: 10., y: -20. }, 1e-9);
+    assert_close(omap.map_to_projected(Coord { x: 10., y: -20. }), c, 1e-6);
+
+    let omap = Omap::new(REF_POINT, Scale::S4_000, None, None).unwrap();
+    assert_close(omap.projected_to_map(c), Coord { x: 25., y: -50. }, 1e-9);
+}
+
+#[test]
+fn matches_the_written_coordinates() {
+    let mut omap = Omap::builder(REF_POINT, Scale::S15_000)
+        .grivation(5_f64.to_radians())
+        .grid_scale_factor(0.9996)
+        .build()
+        .unwrap();
+    omap.set_coordinate_mode(CoordinateMode::Absolute);
+    let c = REF_POINT + Coord { x: 300., y: 450. };
+    omap.add_object(PointObject::from_point(
+        Point::from(c),
+        PointSymbol::DotKnoll,
+        0.,
+    ));
+
+    let mut content = Vec::new();
+    omap.write_to(&mut content, None).unwrap();
+    let content = String::from_utf8(content).unwrap();
+    let (_, objects) = content.split_once("<parts").unwrap();
+    let (_, coords) = objects.split_once(r#"<coords count="1">"#).unwrap();
+    let (x, y) = coords[..coords.find(';').unwrap()].split_once(' ').unwrap();
+    // the file is in 0.001 mm with y pointing down
+    let written = Coord {
+        x: x.parse::<f64>().unwrap() / 1000.,
+        y: -y.parse::<f64>().unwrap() / 1000.,
+    };
+
+    let map = omap.projected_to_map(c);
+    assert_close(map, written, 0.001);
+    // the rotation and the scale factor are applied
+    assert!(map.x < 300. / 15.);
+    assert!((map.x.hypot(map.y) - 300_f64.hypot(450.) / 15. / 0.9996).abs() < 1e-9);
+    assert_close(omap.map_to_projected(map), c, 1e-6);
+}
+
+#[cfg(feature = "geo_ref")]
+#[test]
+fn geographic_coordinates() {
+    let omap = Omap::new(REF_POINT, Scale::S10_000, Some(Crs::Epsg(25832)), None).unwrap();
+    let geographic = omap.map_to_geographic(Coord::zero()).unwrap();
+    let geo_ref_point = omap.get_geo_ref_point().unwrap();
+    assert_close(
+        geographic,
+        Coord {
+            x: geo_ref_point.x.to_degrees(),
+            y: geo_ref_point.y.to_degrees(),
+        },
+        1e-9,
+    );
+    assert!((geographic.x - 10.7).abs() < 0.1 && (geographic.y - 59.9).abs() < 0.1);
+
+    let c = Coord { x: 12.5, y: -30. };
+    let round_trip = omap
+        .geographic_to_map(omap.map_to_geographic(c).unwrap())
+        .unwrap();
+    assert_close(round_trip, c, 1e-6);
+
+    let without_crs = Omap::new(REF_POINT, Scale::S10_000, None, None).unwrap();
+    assert!(matches!(
+        without_crs.geographic_to_map(geographic),
+        Err(OmapError::MissingCrs)
+    ));
+}
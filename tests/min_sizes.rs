@@ -0,0 +1,168 @@
+use geo_types::{Coord, LineString, Polygon};
+use omap::{
+    objects::{AreaObject, LineObject, MapObject},
+    symbols::{AreaSymbol, LineSymbol, PointSymbol, Symbol},
+    CoordinateMode, MinSizePolicy, Omap, Scale,
+};
+use std::collections::HashMap;
+
+fn square(corner: Coord, side: f64) -> Polygon {
+    Polygon::new(
+        LineString::from(vec![
+            corner,
+            corner + Coord { x: side, y: 0. },
+            corner + Coord { x: side, y: side },
+            corner + Coord { x: 0., y: side },
+            corner,
+        ]),
+        vec![],
+    )
+}
+
+fn line(start: Coord, length: f64) -> LineString {
+    LineString::from(vec![start, start + Coord { x: length, y: 0. }])
+}
+
+// at 1:15_000 the minimum marsh is 45 m², the minimum open land 64 m² and the minimum cliff 9 m
+fn map(offset: Coord) -> Omap {
+    let mut omap = Omap::new(offset, Scale::S15_000, None, None).unwrap();
+    if offset != Coord::zero() {
+        omap.set_coordinate_mode(CoordinateMode::Absolute);
+    }
+    for (corner, side) in [
+        (Coord { x: 0., y: 0. }, 5.),
+        (Coord { x: 100., y: 0. }, 10.),
+    ] {
+        omap.add_object(AreaObject::from_polygon(
+            square(offset + corner, side),
+            AreaSymbol::Marsh,
+            0.,
+        ));
+    }
+    omap.add_object(AreaObject::from_polygon(
+        square(offset + Coord { x: 0., y: 100. }, 4.),
+        AreaSymbol::OpenLand,
+        0.,
+    ));
+    for (start, length) in [
+        (Coord { x: 0., y: 200. }, 5.),
+        (Coord { x: 0., y: 300. }, 20.),
+    ] {
+        omap.add_object(LineObject::from_line_string(
+            line(offset + start, length),
+            LineSymbol::Cliff,
+        ));
+    }
+    // contours have no minimum length
+    omap.add_object(LineObject::from_line_string(
+        line(offset, 1.),
+        LineSymbol::Contour,
+    ));
+    omap
+}
+
+fn count(omap: &Omap, symbol: impl Into<Symbol>) -> usize {
+    omap.objects().get(&symbol.into()).map_or(0, Vec::len)
+}
+
+fn counts(symbols: &[(Symbol, usize)]) -> HashMap<Symbol, usize> {
+    symbols.iter().copied().collect()
+}
+
+#[test]
+fn drop_small_objects() {
+    let mut omap = map(Coord::zero());
+    let report = omap.enforce_min_sizes(MinSizePolicy::Drop).unwrap();
+    assert_eq!(
+        report.dropped,
+        counts(&[
+            (AreaSymbol::Marsh.into(), 1),
+            (AreaSymbol::OpenLand.into(), 1),
+            (LineSymbol::Cliff.into(), 1),
+        ])
+    );
+    assert!(report.exaggerated.is_empty() && report.converted.is_empty());
+    assert_eq!(report.num_changed(), 3);
+    assert_eq!(count(&omap, AreaSymbol::Marsh), 1);
+    assert_eq!(count(&omap, AreaSymbol::OpenLand), 0);
+    assert_eq!(count(&omap, LineSymbol::Cliff), 1);
+    assert_eq!(count(&omap, LineSymbol::Contour), 1);
+
+    // nothing is left to change
+    assert_eq!(
+        omap.enforce_min_sizes(MinSizePolicy::Drop)
+            .unwrap()
+            .num_changed(),
+        0
+    );
+}
+
+#[test]
+fn exaggerate_small_objects() {
+    // in absolute coordinates far from the origin
+    let offset = Coord {
+        x: 597_000.,
+        y: 6_643_000.,
+    };
+    let mut omap = map(offset);
+    let report = omap.enforce_min_sizes(MinSizePolicy::Exaggerate).unwrap();
+    assert_eq!(report.num_changed(), 3);
+    assert_eq!(report.exaggerated.len(), 3);
+
+    let sizes: Vec<(f64, Coord)> = omap.objects()[&AreaSymbol::Marsh.into()]
+        .iter()
+        .map(|o| match o {
+            MapObject::AreaObject(a) => {
+                let ring = &a.polygon.exterior().0;
+                let side = ring[1].x - ring[0].x;
+                (side * side, ring[0] + Coord { x: side, y: side } / 2.)
+            }
+            _ => unreachable!(),
+        })
+        .collect();
+    // scaled about its centre to the minimum area
+    let (area, centre) = sizes.iter().find(|(_, c)| c.x - offset.x < 50.).unwrap();
+    assert!((area - 45.).abs() < 1e-6, "{area}");
+    assert!((*centre - (offset + Coord { x: 2.5, y: 2.5 })).x.abs() < 1e-6);
+    assert!((*centre - (offset + Coord { x: 2.5, y: 2.5 })).y.abs() < 1e-6);
+
+    let MapObject::LineObject(cliff) = omap.objects()[&LineSymbol::Cliff.into()]
+        .iter()
+        .find(|o| matches!(o, MapObject::LineObject(l) if l.line.0[0].y - offset.y < 250.))
+        .unwrap()
+    else {
+        unreachable!()
+    };
+    assert!((cliff.line.0[1].x - cliff.line.0[0].x - 9.).abs() < 1e-6);
+
+    // the exaggerated objects are not dropped by a second pass
+    assert_eq!(
+        omap.enforce_min_sizes(MinSizePolicy::Drop)
+            .unwrap()
+            .num_changed(),
+        0
+    );
+}
+
+#[test]
+fn convert_small_objects_to_points() {
+    let mut omap = map(Coord::zero());
+    let report = omap
+        .enforce_min_sizes(MinSizePolicy::ConvertToPoint)
+        .unwrap();
+    assert_eq!(
+        report.converted,
+        counts(&[(AreaSymbol::Marsh.into(), 1), (LineSymbol::Cliff.into(), 1)])
+    );
+    // open land has no point symbol
+    assert_eq!(report.dropped, counts(&[(AreaSymbol::OpenLand.into(), 1)]));
+
+    assert_eq!(count(&omap, AreaSymbol::Marsh), 1);
+    assert_eq!(count(&omap, PointSymbol::MinimumMarsh), 1);
+    assert_eq!(count(&omap, PointSymbol::MinimumCliff), 1);
+    let MapObject::PointObject(marsh) = &omap.objects()[&PointSymbol::MinimumMarsh.into()][0]
+    else {
+        unreachable!()
+    };
+    assert!((marsh.point.x() - 2.5).abs() < 1e-9 && (marsh.point.y() - 2.5).abs() < 1e-9);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "geo_ref")]
+
+use geo_types::Coord;
+use omap::{Crs, Omap, Scale};
+
+fn declination(omap: &Omap) -> String {
+    let mut content = Vec::new();
+    omap.write_to(&mut content, None).unwrap();
+    let content = String::from_utf8(content).unwrap();
+    let start = content.find(" declination=\"").unwrap() + 14;
+    let end = start + content[start..].find('"').unwrap();
+    content[start..end].to_string()
+}
+
+const OSLO: Coord = Coord {
+    x: 597_000.,
+    y: 6_643_000.,
+};
+const MUNICH: Coord = Coord {
+    x: 691_000.,
+    y: 5_335_000.,
+};
+
+#[test]
+fn computed_declination_keeps_its_date() {
+    let date = chrono::NaiveDate::from_ymd_opt(2021, 6, 1).unwrap();
+    let mut omap = Omap::builder(OSLO, Scale::S10_000)
+        .crs(Crs::Epsg(25832))
+        .declination_date(date)
+        .build()
+        .unwrap();
+    omap.reproject(Crs::Epsg(25832), MUNICH).unwrap();
+
+    let expected = Omap::builder(MUNICH, Scale::S10_000)
+        .crs(Crs::Epsg(25832))
+        .declination_date(date)
+        .build()
+        .unwrap();
+    assert_eq!(declination(&omap), declination(&expected));
+}
+
+#[test]
+fn given_declination_is_kept() {
+    let mut omap = Omap::builder(OSLO, Scale::S10_000)
+        .crs(Crs::Epsg(25832))
+        .declination(0.1)
+        .build()
+        .unwrap();
+    let given = declination(&omap);
+    omap.reproject(Crs::Epsg(25832), MUNICH).unwrap();
+    assert_eq!(declination(&omap), given);
+}
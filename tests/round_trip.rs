@@ -0,0 +1,297 @@
+use geo_types::{Coord, LineString, Point, Polygon};
+use omap::{
+    objects::{AreaObject, LineObject, MapObject, PointObject, TagTrait, TextObject},
+    symbols::{
+        AreaSymbol, LineSymbol, PointSymbol, SprintLineSymbol, Symbol, SymbolSet, TextSymbol,
+    },
+    Omap, Scale,
+};
+#[cfg(feature = "geo_ref")]
+use omap::{Crs, OmapError};
+
+// the distance from a point to the closest segment of a line
+fn distance_to_line(p: Coord, line: &LineString) -> f64 {
+    line.lines()
+        .map(|l| {
+            let d = l.delta();
+            let t = (((p - l.start).x * d.x + (p - l.start).y * d.y) / (d.x * d.x + d.y * d.y))
+                .clamp(0., 1.);
+            let closest = l.start + d * t;
+            (p - closest).x.hypot((p - closest).y)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn max_deviation(original: &LineString, read: &LineString) -> f64 {
+    original
+        .0
+        .iter()
+        .map(|&p| distance_to_line(p, read))
+        .fold(0., f64::max)
+}
+
+fn circle(center: Coord, radius: f64, n: usize, clockwise: bool) -> LineString {
+    let mut coords: Vec<Coord> = (0..n)
+        .map(|i| {
+            let a = i as f64 / n as f64 * std::f64::consts::TAU;
+            center
+                + Coord {
+                    x: radius * a.cos(),
+                    y: radius * a.sin(),
+                }
+        })
+        .collect();
+    if clockwise {
+        coords.reverse();
+    }
+    coords.push(coords[0]);
+    LineString::new(coords)
+}
+
+fn write_and_read(omap: &Omap, name: &str, bezier_error: Option<f64>) -> Omap {
+    let path = std::env::temp_dir().join(format!("omap_round_trip_{name}.omap"));
+    omap.write_to_file(path.clone(), bezier_error).unwrap();
+    let read = Omap::read_from_file(path.clone()).unwrap();
+    std::fs::remove_file(path).unwrap();
+    read
+}
+
+fn objects(omap: &Omap, symbol: impl Into<Symbol>) -> &[MapObject] {
+    omap.get_current_part()
+        .unwrap()
+        .objects
+        .get(&symbol.into())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+#[test]
+fn chained_bezier_curves() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    let sine = LineString::new(
+        (0..200)
+            .map(|i| Coord {
+                x: i as f64 * 5.,
+                y: 100. * (i as f64 / 20.).sin(),
+            })
+            .collect(),
+    );
+    omap.add_object(LineObject::from_line_string(
+        sine.clone(),
+        LineSymbol::Contour,
+    ));
+
+    let read = write_and_read(&omap, "curves", Some(1.));
+    let lines = objects(&read, LineSymbol::Contour);
+    assert_eq!(lines.len(), 1);
+    let MapObject::LineObject(line) = &lines[0] else {
+        panic!("a line object was read as another object");
+    };
+    assert!(max_deviation(&sine, &line.line) < 1.5);
+    assert!(max_deviation(&line.line, &sine) < 1.5);
+}
+
+#[test]
+fn areas_with_holes() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    let exterior = circle(Coord::zero(), 200., 100, false);
+    let interior = circle(Coord { x: 50., y: 0. }, 60., 50, true);
+    omap.add_object(AreaObject::from_polygon(
+        Polygon::new(exterior.clone(), vec![interior.clone()]),
+        AreaSymbol::Marsh,
+        0.,
+    ));
+
+    for (name, bezier_error) in [("holes", None), ("curved_holes", Some(0.5))] {
+        let read = write_and_read(&omap, name, bezier_error);
+        let areas = objects(&read, AreaSymbol::Marsh);
+        assert_eq!(areas.len(), 1);
+        let MapObject::AreaObject(area) = &areas[0] else {
+            panic!("an area object was read as another object");
+        };
+        assert_eq!(area.polygon.interiors().len(), 1);
+        assert!(max_deviation(&exterior, area.polygon.exterior()) < 1.);
+        assert!(max_deviation(&interior, &area.polygon.interiors()[0]) < 1.);
+    }
+}
+
+#[test]
+fn modified_built_in_symbol_sets() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    omap.add_object(LineObject::from_line_string(
+        circle(Coord::zero(), 50., 20, false),
+        LineSymbol::Contour,
+    ));
+    let read = write_and_read(&omap, "built_in_symbols", None);
+    assert_eq!(read.get_symbol_set(), &SymbolSet::Isom2017_2);
+    let enlarged = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+    let read = write_and_read(&enlarged, "enlarged_symbols", None);
+    assert_eq!(read.get_symbol_set(), &SymbolSet::Isom2017_2Enlarged);
+
+    // ISOM 2017-2 with a color renamed and recolored and a symbol renamed as in Mapper
+    let black = r#"name="Black 100%" c="0" m="0" y="0" k="1""#;
+    let recolored = r#"name="Club black" c="0.1" m="0.1" y="0.1" k="0.9""#;
+    let mut content = Vec::new();
+    omap.write_to(&mut content, None).unwrap();
+    let content = String::from_utf8(content).unwrap();
+    assert!(content.contains(black));
+    let edited = content.replace(black, recolored).replacen(
+        r#"name="Contour""#,
+        r#"name="Height curve""#,
+        1,
+    );
+    let read = read_content(&edited, "recolored");
+    assert_eq!(read.get_symbol_set(), &SymbolSet::Isom2017_2);
+    assert_eq!(objects(&read, LineSymbol::Contour).len(), 1);
+    let mut written = Vec::new();
+    read.write_to(&mut written, None).unwrap();
+    let written = String::from_utf8(written).unwrap();
+    assert!(written.contains(recolored));
+    assert!(written.contains(r#"name="Height curve""#));
+
+    // a set with another name is unknown
+    let read = read_content(
+        &content.replace(r#"id="ISOM 2017-2""#, r#"id="Club set""#),
+        "renamed_set",
+    );
+    assert!(matches!(read.get_symbol_set(), SymbolSet::Custom(_)));
+}
+
+fn read_content(content: &str, name: &str) -> Omap {
+    let path = std::env::temp_dir().join(format!("omap_round_trip_{name}.omap"));
+    std::fs::write(&path, content).unwrap();
+    let read = Omap::read_from_file(path.clone()).unwrap();
+    std::fs::remove_file(path).unwrap();
+    read
+}
+
+#[test]
+fn tags_text_and_rotations() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    let special = "a & b <c> \"d\" 'e'\tf\ng";
+    let mut point =
+        PointObject::from_point(Point::new(10., 20.), PointSymbol::SlopeLineContour, 1.);
+    point.add_tag("note", special);
+    omap.add_object(point);
+    let mut text = TextObject::from_point(
+        Point::new(-10., 5.),
+        TextSymbol::SpotHeight,
+        special.to_string(),
+    );
+    text.rotation = Some(-0.5);
+    omap.add_object(text);
+    omap.add_object(TextObject::from_point(
+        Point::new(0., 0.),
+        TextSymbol::ContourValue,
+        "105".to_string(),
+    ));
+
+    let read = write_and_read(&omap, "tags", None);
+    let MapObject::PointObject(point) = &objects(&read, PointSymbol::SlopeLineContour)[0] else {
+        panic!("a point object was read as another object");
+    };
+    assert_eq!(point.tags.get("note").map(String::as_str), Some(special));
+    assert!((point.rotation - 1.).abs() < 1e-9);
+    assert!((point.point.0 - Coord { x: 10., y: 20. }).x.abs() < 1e-2);
+
+    let MapObject::TextObject(text) = &objects(&read, TextSymbol::SpotHeight)[0] else {
+        panic!("a text object was read as another object");
+    };
+    assert_eq!(text.text, special);
+    assert!((text.rotation.unwrap() + 0.5).abs() < 1e-9);
+    let MapObject::TextObject(upright) = &objects(&read, TextSymbol::ContourValue)[0] else {
+        panic!("a text object was read as another object");
+    };
+    assert_eq!(upright.rotation, None);
+}
+
+#[cfg(feature = "geo_ref")]
+#[test]
+fn epsg_codes_without_projection() {
+    let ref_point = Coord {
+        x: 500_000.,
+        y: 6_000_000.,
+    };
+    let crs = Crs::Epsg(100_000);
+    let error = Omap::builder(ref_point, Scale::S10_000)
+        .crs(crs.clone())
+        .build()
+        .unwrap_err();
+    assert!(matches!(error, OmapError::UnsupportedCrs(_)));
+
+    let omap = Omap::builder(ref_point, Scale::S10_000)
+        .crs(crs.clone())
+        .declination(0.1)
+        .build()
+        .unwrap();
+    let mut content = Vec::new();
+    omap.write_to(&mut content, None).unwrap();
+    let content = String::from_utf8(content).unwrap();
+    assert!(content.contains(">EPSG:100000</spec>"));
+    assert!(!content.contains("+init=epsg"));
+    let read = write_and_read(&omap, "large_epsg", None);
+    assert_eq!(read.get_crs(), Some(&crs));
+}
+
+#[cfg(feature = "geo_ref")]
+#[test]
+fn overridden_georeferencing() {
+    let build = |crs: Crs| {
+        Omap::builder(
+            Coord {
+                x: 597_000.,
+                y: 6_643_000.,
+            },
+            Scale::S10_000,
+        )
+        .crs(crs)
+        .declination(0.1)
+        .grivation(0.05)
+        .grid_scale_factor(0.9996)
+        .build()
+    };
+
+    // the CRS is still checked and the geographic ref point written when all parameters are given
+    let omap = build(Crs::Epsg(25832)).unwrap();
+    let read = write_and_read(&omap, "overridden", None);
+    let geo_ref_point = read.get_geo_ref_point().unwrap();
+    assert!((geo_ref_point.x.to_degrees() - 10.75).abs() < 0.05);
+    assert!((geo_ref_point.y.to_degrees() - 59.91).abs() < 0.05);
+    let mut content = Vec::new();
+    read.write_to(&mut content, None).unwrap();
+    assert!(String::from_utf8(content)
+        .unwrap()
+        .contains(r#"grid_scale_factor="0.9996""#));
+
+    assert!(matches!(
+        build(Crs::Proj("+proj=unknown".to_string())),
+        Err(OmapError::Proj(_))
+    ));
+}
+
+#[test]
+fn sprint_maps() {
+    // ISOM is written at every scale unless ISSprOM is set
+    let mut omap = Omap::new(Coord::zero(), Scale::S4_000, None, None).unwrap();
+    omap.add_object(LineObject::from_line_string(
+        circle(Coord::zero(), 20., 20, false),
+        LineSymbol::Contour,
+    ));
+    let read = write_and_read(&omap, "isom_4000", None);
+    assert_eq!(read.get_symbol_set(), &SymbolSet::Isom2017_2Enlarged);
+    assert_eq!(objects(&read, LineSymbol::Contour).len(), 1);
+
+    omap.set_symbol_set(SymbolSet::Issprom2019_2);
+    assert!(omap.write_to(Vec::new(), None).is_err());
+
+    let contour = LineSymbol::Sprint(SprintLineSymbol::Contour);
+    let mut omap = Omap::new(Coord::zero(), Scale::S4_000, None, None).unwrap();
+    omap.set_symbol_set(SymbolSet::Issprom2019_2);
+    omap.add_object(LineObject::from_line_string(
+        circle(Coord::zero(), 20., 20, false),
+        contour,
+    ));
+    let read = write_and_read(&omap, "issprom_4000", None);
+    assert_eq!(read.get_symbol_set(), &SymbolSet::Issprom2019_2);
+    assert_eq!(objects(&read, contour).len(), 1);
+}
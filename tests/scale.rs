@@ -0,0 +1,82 @@
+use geo_types::{Coord, Point};
+use omap::{
+    objects::{MapObject, PointObject},
+    symbols::{
+        AreaSymbol, LineSymbol, PointSymbol, SprintAreaSymbol, SprintLineSymbol, SymbolTrait,
+    },
+    Omap, Scale,
+};
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9 * b.abs().max(1.)
+}
+
+#[test]
+fn min_sizes_follow_the_denominator() {
+    // the ISOM sizes are given at 1:15_000 and scale with the denominator
+    let marsh = AreaSymbol::Marsh.min_size(Scale::S15_000);
+    assert!(close(
+        AreaSymbol::Marsh.min_size(Scale::S10_000),
+        marsh * (10. / 15_f64).powi(2)
+    ));
+    assert!(close(
+        AreaSymbol::Marsh.min_size(Scale::S20_000),
+        marsh * (20. / 15_f64).powi(2)
+    ));
+    assert!(close(LineSymbol::Cliff.min_size(Scale::S15_000), 9.));
+    assert!(close(LineSymbol::Cliff.min_size(Scale::S7_500), 4.5));
+
+    // the ISSprOM sizes are given at 1:4_000
+    let sprint_marsh = AreaSymbol::Sprint(SprintAreaSymbol::Marsh);
+    assert!(close(sprint_marsh.min_size(Scale::S4_000), 3.2));
+    assert!(close(
+        sprint_marsh.min_size(Scale::S5_000),
+        3.2 * (5. / 4_f64).powi(2)
+    ));
+    let sprint_contour = LineSymbol::Sprint(SprintLineSymbol::FormLine);
+    assert!(close(
+        sprint_contour.min_size(Scale::S5_000),
+        sprint_contour.min_size(Scale::S4_000) * 1.25
+    ));
+
+    assert_eq!(AreaSymbol::Custom(1000).min_size(Scale::S15_000), 0.);
+}
+
+#[test]
+fn map_coordinates_follow_the_denominator() {
+    for (denominator, coords) in [
+        (4_000, "2500 -5000;"),
+        (7_500, "1333 -2667;"),
+        (10_000, "1000 -2000;"),
+        (20_000, "500 -1000;"),
+    ] {
+        let scale = Scale::new(denominator).unwrap();
+        let mut omap = Omap::new(Coord::zero(), scale, None, None).unwrap();
+        omap.add_object(PointObject::from_point(
+            Point::new(10., 20.),
+            PointSymbol::DotKnoll,
+            0.,
+        ));
+        let mut content = Vec::new();
+        omap.write_to(&mut content, None).unwrap();
+        let content = String::from_utf8(content).unwrap();
+        assert!(
+            content.contains(&format!(r#"<georeferencing scale="{denominator}""#)),
+            "1:{denominator}"
+        );
+        assert!(content.contains(coords), "1:{denominator}");
+
+        // and back to meters when read
+        let path = std::env::temp_dir().join(format!("omap_scale_{denominator}.omap"));
+        std::fs::write(&path, content).unwrap();
+        let read = Omap::read_from_file(path.clone()).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(read.get_scale(), scale);
+        let MapObject::PointObject(point) = &read.objects()[&PointSymbol::DotKnoll.into()][0]
+        else {
+            panic!("a point object was read as another object");
+        };
+        assert!((point.point.x() - 10.).abs() < 0.01);
+        assert!((point.point.y() - 20.).abs() < 0.01);
+    }
+}
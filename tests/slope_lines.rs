@@ -0,0 +1,109 @@
+use geo_types::{Coord, LineString};
+use omap::{
+    objects::{LineObject, MapObject, TagTrait},
+    symbols::{LineSymbol, PointSymbol},
+    Omap, Scale,
+};
+
+fn circle(center: Coord, radius: f64, n: usize, clockwise: bool) -> LineString {
+    let mut coords: Vec<Coord> = (0..n)
+        .map(|i| {
+            let a = i as f64 / n as f64 * std::f64::consts::TAU;
+            center
+                + Coord {
+                    x: radius * a.cos(),
+                    y: radius * a.sin(),
+                }
+        })
+        .collect();
+    if clockwise {
+        coords.reverse();
+    }
+    coords.push(coords[0]);
+    LineString::new(coords)
+}
+
+fn contour(line: LineString, elevation: Option<f64>) -> LineObject {
+    let mut contour = LineObject::from_line_string(line, LineSymbol::Contour);
+    if let Some(elevation) = elevation {
+        contour.add_elevation_tag(elevation);
+    }
+    contour
+}
+
+// the positions and the directions the slope lines point in
+fn slope_lines(omap: &Omap) -> Vec<(Coord, Coord)> {
+    omap.objects()
+        .get(&PointSymbol::SlopeLineContour.into())
+        .map(|slope_lines| {
+            slope_lines
+                .iter()
+                .map(|o| match o {
+                    MapObject::PointObject(p) => (
+                        p.point.0,
+                        // the symbol points up unrotated and is rotated counter clockwise
+                        Coord {
+                            x: -p.rotation.sin(),
+                            y: p.rotation.cos(),
+                        },
+                    ),
+                    _ => unreachable!(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn clockwise_loops_are_depressions() {
+    let center = Coord { x: 50., y: -20. };
+    for (spacing, count) in [(40., 3), (20., 6), (1000., 1)] {
+        // the loop is about 125 m long
+        let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+        omap.add_object(contour(circle(center, 20., 32, true), None));
+        omap.add_object(contour(
+            circle(Coord { x: 200., y: 0. }, 20., 32, false),
+            None,
+        ));
+
+        assert_eq!(omap.add_depression_slope_lines(spacing).unwrap(), count);
+        let slope_lines = slope_lines(&omap);
+        assert_eq!(slope_lines.len(), count);
+        for (position, direction) in slope_lines {
+            // on the clockwise loop pointing to its centre
+            assert!(((position - center).x.hypot((position - center).y) - 20.).abs() < 1e-9);
+            let inside = (center - position) / 20.;
+            assert!((direction - inside).x.hypot((direction - inside).y) < 0.01);
+        }
+    }
+}
+
+#[test]
+fn depressions_from_elevations() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+    // a counter clockwise depression and a clockwise knoll inside a 100 m contour
+    omap.add_object(contour(circle(Coord::zero(), 200., 64, false), Some(100.)));
+    omap.add_object(contour(
+        circle(Coord { x: -100., y: 0. }, 20., 32, false),
+        Some(95.),
+    ));
+    omap.add_object(contour(
+        circle(Coord { x: 100., y: 0. }, 20., 32, true),
+        Some(105.),
+    ));
+
+    assert_eq!(omap.add_depression_slope_lines(1000.).unwrap(), 1);
+    let (position, direction) = slope_lines(&omap)[0];
+    assert!((position.x + 100.).abs() <= 20.);
+    let inside = (Coord { x: -100., y: 0. } - position) / 20.;
+    assert!((direction - inside).x.hypot((direction - inside).y) < 0.01);
+}
+
+#[test]
+fn open_contours_get_no_slope_lines() {
+    let mut omap = Omap::new(Coord::zero(), Scale::S10_000, None, None).unwrap();
+    let mut open = circle(Coord::zero(), 20., 32, true);
+    let _ = open.0.pop();
+    omap.add_object(contour(open, None));
+    assert_eq!(omap.add_depression_slope_lines(10.).unwrap(), 0);
+}
@@ -0,0 +1,120 @@
+use geo_types::{Coord, LineString, Polygon};
+use omap::{
+    objects::{AreaObject, LineObject, MapObject, TagTrait},
+    symbols::{AreaSymbol, LineSymbol, Symbol},
+    Omap, ReplaceFilter, Scale,
+};
+
+fn line(y: f64, id: &str) -> LineObject {
+    let mut line = LineObject::from_line_string(
+        LineString::new(vec![Coord { x: -100., y }, Coord { x: 100., y }]),
+        LineSymbol::Contour,
+    );
+    line.add_tag("id", id);
+    line
+}
+
+fn marsh() -> AreaObject {
+    let square = LineString::new(vec![
+        Coord { x: 0., y: 0. },
+        Coord { x: 50., y: 0. },
+        Coord { x: 50., y: 50. },
+        Coord { x: 0., y: 50. },
+        Coord { x: 0., y: 0. },
+    ]);
+    AreaObject::from_polygon(Polygon::new(square, vec![]), AreaSymbol::Marsh, 0.)
+}
+
+// the sorted ids of the objects with the symbol in the current part
+fn ids(omap: &Omap, symbol: impl Into<Symbol>) -> Vec<String> {
+    let objects = omap.get_current_part().unwrap().objects.get(&symbol.into());
+    let mut ids: Vec<String> = objects
+        .into_iter()
+        .flatten()
+        .map(|o| match o {
+            MapObject::LineObject(l) => l.tags.get("id").cloned().unwrap_or_default(),
+            MapObject::AreaObject(a) => a.tags.get("id").cloned().unwrap_or_default(),
+            _ => String::new(),
+        })
+        .collect();
+    ids.sort();
+    ids
+}
+
+// write the `existing` map to a file, update it with the `new` map and read it back
+fn update(existing: &Omap, new: &Omap, filter: &ReplaceFilter, name: &str) -> Omap {
+    let path = std::env::temp_dir().join(format!("omap_update_{name}.omap"));
+    existing.write_to_file(path.clone(), None).unwrap();
+    new.update_file(path.clone(), filter, None).unwrap();
+    let read = Omap::read_from_file(path.clone()).unwrap();
+    std::fs::remove_file(path).unwrap();
+    read
+}
+
+#[test]
+fn replace_by_symbol() {
+    let mut existing = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    existing.add_object(line(0., "old 1"));
+    existing.add_object(line(10., "old 2"));
+    existing.add_object(marsh());
+
+    let mut new = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    new.add_object(line(20., "new"));
+
+    let filter = ReplaceFilter::Symbols(vec![LineSymbol::Contour.into()]);
+    let read = update(&existing, &new, &filter, "symbols");
+    assert_eq!(ids(&read, LineSymbol::Contour), ["new"]);
+    assert_eq!(ids(&read, AreaSymbol::Marsh).len(), 1);
+}
+
+#[test]
+fn replace_by_tag() {
+    let mut existing = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    let mut generated = line(0., "generated");
+    generated.add_tag("source", "lidar");
+    existing.add_object(generated);
+    existing.add_object(line(10., "manual"));
+
+    let mut new = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    let mut regenerated = line(20., "regenerated");
+    regenerated.add_tag("source", "lidar");
+    new.add_object(regenerated);
+
+    let filter = ReplaceFilter::Tag("source".to_string(), "lidar".to_string());
+    let read = update(&existing, &new, &filter, "tag");
+    assert_eq!(ids(&read, LineSymbol::Contour), ["manual", "regenerated"]);
+}
+
+#[test]
+fn replace_in_a_file_edited_in_mapper() {
+    let mut existing = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    existing.add_object(line(0., "old"));
+    existing.add_object(marsh());
+    let path = std::env::temp_dir().join("omap_update_edited.omap");
+    existing.write_to_file(path.clone(), None).unwrap();
+
+    // a color and a symbol renamed and a color changed in Mapper
+    let content = std::fs::read_to_string(&path)
+        .unwrap()
+        .replacen(r#"name="Black 100%""#, r#"name="Club black""#, 1)
+        .replacen(r#"name="Contour""#, r#"name="Höhenlinie""#, 1)
+        .replacen(
+            r#"c="0" m="0" y="0" k="1""#,
+            r#"c="0.1" m="0.1" y="0.1" k="0.9""#,
+            1,
+        );
+    std::fs::write(&path, &content).unwrap();
+
+    let mut new = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    new.add_object(line(20., "new"));
+    let filter = ReplaceFilter::Symbols(vec![LineSymbol::Contour.into()]);
+    new.update_file(path.clone(), &filter, None).unwrap();
+
+    let updated = std::fs::read_to_string(&path).unwrap();
+    let read = Omap::read_from_file(path.clone()).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert!(updated.contains(r#"name="Club black""#));
+    assert!(updated.contains(r#"name="Höhenlinie""#));
+    assert_eq!(ids(&read, LineSymbol::Contour), ["new"]);
+    assert_eq!(ids(&read, AreaSymbol::Marsh).len(), 1);
+}
@@ -0,0 +1,108 @@
+use geo_types::{Coord, LineString, Point, Polygon};
+use omap::{
+    objects::{AreaObject, LineObject, PointObject},
+    symbols::{AreaSymbol, LineSymbol, PointSymbol},
+    ObjectOrder, Omap, Scale,
+};
+
+fn map() -> Omap {
+    let mut omap = Omap::new(Coord::zero(), Scale::S15_000, None, None).unwrap();
+    omap.add_object(PointObject::from_point(
+        Point::new(10., 20.),
+        PointSymbol::DotKnoll,
+        0.,
+    ));
+    omap.add_object(LineObject::from_line_string(
+        LineString::from(vec![(0., 0.), (30., 10.), (60., 0.), (90., 10.)]),
+        LineSymbol::Contour,
+    ));
+    omap.add_object(AreaObject::from_polygon(
+        Polygon::new(
+            LineString::from(vec![(0., 0.), (50., 0.), (50., 50.), (0., 50.), (0., 0.)]),
+            vec![],
+        ),
+        AreaSymbol::RoughOpenLand,
+        0.,
+    ));
+    omap
+}
+
+fn write(omap: &Omap, bezier_error: Option<f64>) -> Vec<u8> {
+    let mut content = Vec::new();
+    omap.write_to(&mut content, bezier_error).unwrap();
+    content
+}
+
+#[test]
+fn write_the_same_map_twice() {
+    let mut omap = map();
+
+    let first = write(&omap, None);
+    assert_eq!(first, write(&omap, None));
+
+    let path = std::env::temp_dir().join("omap_write_the_same_map_twice.omap");
+    omap.write_to_file(path.clone(), None).unwrap();
+    let written = std::fs::read(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(first, written);
+
+    // the map is kept after writing and can be changed and written again
+    omap.add_object(PointObject::from_point(
+        Point::new(30., 40.),
+        PointSymbol::DotKnoll,
+        0.,
+    ));
+    let second = String::from_utf8(write(&omap, None)).unwrap();
+    assert!(second.contains("2000 -2667;"));
+    let (_, objects) = second.split_once("<parts").unwrap();
+    assert_eq!(objects.matches("<object ").count(), 4);
+}
+
+// the symbol ids of the written objects in the order they are written
+fn symbol_order(omap: &Omap) -> Vec<u16> {
+    let content = String::from_utf8(write(omap, None)).unwrap();
+    let (_, objects) = content.split_once("<parts").unwrap();
+    objects
+        .split("<object ")
+        .skip(1)
+        .map(|o| {
+            let (_, symbol) = o.split_once("symbol=\"").unwrap();
+            symbol[..symbol.find('"').unwrap()].parse().unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn identical_maps_are_written_identically() {
+    // every map gets its own hash map seeds
+    let first = write(&map(), None);
+    for _ in 0..10 {
+        assert_eq!(first, write(&map(), None));
+    }
+}
+
+#[test]
+fn objects_are_ordered_by_symbol() {
+    let mut omap = map();
+    omap.add_object(PointObject::from_point(
+        Point::new(30., 40.),
+        PointSymbol::DotKnoll,
+        0.,
+    ));
+
+    // the yellow open land is drawn below the brown contours and knolls
+    assert_eq!(omap.get_object_order(), &ObjectOrder::DrawingPriority);
+    assert_eq!(symbol_order(&omap), [80, 0, 17, 17]);
+
+    omap.set_object_order(ObjectOrder::SymbolId);
+    assert_eq!(symbol_order(&omap), [0, 17, 17, 80]);
+
+    omap.set_object_order(ObjectOrder::Symbols(vec![PointSymbol::DotKnoll.into()]));
+    assert_eq!(symbol_order(&omap), [17, 17, 80, 0]);
+
+    // objects with the same symbol keep the order they were added in
+    let content = String::from_utf8(write(&omap, None)).unwrap();
+    let first = content.find("667 -1333;").unwrap();
+    let second = content.find("2000 -2667;").unwrap();
+    assert!(first < second);
+}